@@ -1,2 +1,16 @@
+pub(crate) mod diff;
+mod encoding;
+pub(crate) mod error;
+pub(crate) mod export;
+pub(crate) mod format;
+mod layout;
+pub(crate) mod lookup;
 pub(crate) mod manifest;
-pub(crate) mod record;
\ No newline at end of file
+pub(crate) mod name;
+pub(crate) mod record;
+pub(crate) mod reverse;
+pub(crate) mod serial;
+pub(crate) mod signer;
+mod tokenizer;
+pub(crate) mod validator;
+pub(crate) mod zone;
\ No newline at end of file
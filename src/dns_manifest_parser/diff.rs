@@ -0,0 +1,218 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use rocket::serde::Serialize;
+use crate::dns_manifest_parser::manifest::Manifest;
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::record::{OriginRecord, RecordData};
+use crate::dns_manifest_parser::record::wire::canonical_rdata;
+
+// ------------------------------------------- Change ----------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) enum Change {
+    Added(String),
+    Removed(String),
+    // a record of the same RRset was replaced, e.g. its value or TTL changed.
+    Changed{ from: String, to: String },
+}
+
+// ---------------------------------------- RecordChange -------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RecordChange {
+    // canonical owner name of the RRset.
+    name: String,
+    record_type: String,
+    #[serde(flatten)]
+    change: Change,
+}
+
+// -------------------------------------------- Diff -----------------------------------------------
+
+/// Diff lists the changes between two versions of a manifest, records being written in zone
+/// format.
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Diff {
+    from_serial: u32,
+    to_serial: u32,
+    origin: Option<Change>,
+    ttl: Option<Change>,
+    soa: Option<Change>,
+    records: Vec<RecordChange>,
+}
+
+impl Diff {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.origin.is_none() && self.ttl.is_none() && self.soa.is_none() && self.records.is_empty()
+    }
+}
+
+/// diff compares two manifests. Records are matched by owner name, type and rdata: names are
+/// compared case-insensitively, and relative names match their absolute form, in owner names
+/// and in rdata alike.
+pub(crate) fn diff(from: &Manifest, to: &Manifest) -> Diff {
+    let mut removed: Vec<(Key, &RecordData)> = keys(from);
+    let mut added: Vec<(Key, &RecordData)> = vec![];
+    let mut changes: Vec<RecordChange> = vec![];
+
+    for (key, r) in keys(to) {
+        match removed.iter().position(|(k, _)| *k == key) {
+            Some(i) => {
+                let (_, o) = removed.remove(i);
+                // same record, with another TTL or class.
+                if written(o, from.get_origin()) != written(r, to.get_origin()) {
+                    changes.push(key.change(Change::Changed{ from: o.to_string(), to: r.to_string() }))
+                }
+            }
+            None => added.push((key, r)),
+        }
+    }
+
+    // what remains of a RRset on both sides was replaced.
+    for (key, r) in added {
+        match removed.iter().position(|(k, _)| k.name == key.name && k.record_type == key.record_type) {
+            Some(i) => {
+                let (_, o) = removed.remove(i);
+                changes.push(key.change(Change::Changed{ from: o.to_string(), to: r.to_string() }))
+            }
+            None => changes.push(key.change(Change::Added(r.to_string()))),
+        }
+    }
+    changes.extend(removed.into_iter().map(|(key, o)| key.change(Change::Removed(o.to_string()))));
+
+    Diff{
+        from_serial: from.get_soa().get_serial(),
+        to_serial: to.get_soa().get_serial(),
+        origin: compare(from.get_origin(), to.get_origin()),
+        ttl: compare(from.get_ttl(), to.get_ttl()),
+        soa: compare(from.get_soa(), to.get_soa()),
+        records: changes,
+    }
+}
+
+// written returns a record in a form comparing the same when only the way its names are written
+// changes.
+fn written(record: &RecordData, origin: &OriginRecord) -> String {
+    record.resolve(origin).to_string().to_ascii_lowercase()
+}
+
+fn compare(from: &dyn Display, to: &dyn Display) -> Option<Change> {
+    let (from, to) = (from.to_string(), to.to_string());
+    (from != to).then_some(Change::Changed{ from, to })
+}
+
+// Key identifies a record across versions of a manifest.
+#[derive(PartialEq)]
+struct Key {
+    name: String,
+    record_type: String,
+    // canonical form of the rdata, e.g. the same for `web` and `web.<origin>`.
+    rdata: Vec<u8>,
+}
+
+impl Key {
+    fn change(&self, change: Change) -> RecordChange {
+        RecordChange{ name: self.name.to_string(), record_type: self.record_type.to_string(), change }
+    }
+}
+
+fn keys(manifest: &Manifest) -> Vec<(Key, &RecordData)> {
+    let origin: &OriginRecord = manifest.get_origin();
+    manifest.get_records()
+        .iter()
+        .map(|r| (Key{
+            name: r.get_name().to_canonical(origin),
+            record_type: r.get_type().to_string(),
+            rdata: canonical_rdata(r, origin).unwrap_or_else(|_| r.get_rdata().into_bytes()),
+        }, r))
+        .collect()
+}
+
+// Diff renders as a unified diff, with one hunk per change. Like `diff -u`, identical manifests
+// render as nothing.
+impl Display for Diff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(())
+        }
+        writeln!(f, "--- serial {}", self.from_serial)?;
+        writeln!(f, "+++ serial {}", self.to_serial)?;
+        let directives = [(record::ORIGIN, &self.origin), (record::TTL, &self.ttl), (record::SOA, &self.soa)];
+        for (label, change) in directives {
+            if let Some(change) = change {
+                write_hunk(f, label, change)?
+            }
+        }
+        for r in &self.records {
+            write_hunk(f, &format!("{} {}", r.name, r.record_type), &r.change)?
+        }
+        Ok(())
+    }
+}
+
+fn write_hunk(f: &mut Formatter<'_>, label: &str, change: &Change) -> fmt::Result {
+    writeln!(f, "@@ {} @@", label)?;
+    match change {
+        Change::Added(r) => writeln!(f, "+{}", r),
+        Change::Removed(r) => writeln!(f, "-{}", r),
+        Change::Changed{ from, to } => writeln!(f, "-{}\n+{}", from, to),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+www IN CNAME web
+web IN A 10.0.0.5
+web IN A 10.0.0.6
+@ IN MX 10 mail
+";
+
+    fn diff(to: &str) -> String {
+        let from = Manifest::parse(ZONE, ParseMode::Strict).unwrap().0;
+        let to = Manifest::parse(to, ParseMode::Strict).unwrap().0;
+        from.diff(&to).to_string()
+    }
+
+    #[test]
+    fn names_written_differently_are_the_same() {
+        let to = ZONE
+            .replace("www IN CNAME web", "WWW.example.com. IN CNAME Web.Example.COM.")
+            .replace("10 mail", "10 mail.example.com.");
+        assert_eq!(diff(&to), "");
+        assert_eq!(diff(ZONE), "");
+    }
+
+    #[test]
+    fn changes_are_matched_by_rrset() {
+        let to = ZONE
+            .replace(" 1 7200", " 2 7200")
+            .replace("www IN CNAME web", "www IN CNAME web2")
+            .replace("web IN A 10.0.0.6", "web 60 IN A 10.0.0.6")
+            .replace("@ IN MX 10 mail\n", "")
+            + "api IN A 10.0.0.7\n";
+        assert_eq!(diff(&to), "--- serial 1
++++ serial 2
+@@ SOA @@
+-@ IN SOA ns1 admin 1 7200 3600 1209600 3600
++@ IN SOA ns1 admin 2 7200 3600 1209600 3600
+@@ web.example.com. A @@
+-web IN A 10.0.0.6
++web 60 IN A 10.0.0.6
+@@ www.example.com. CNAME @@
+-www IN CNAME web
++www IN CNAME web2
+@@ api.example.com. A @@
++api IN A 10.0.0.7
+@@ example.com. MX @@
+-@ IN MX 10 mail
+");
+    }
+}
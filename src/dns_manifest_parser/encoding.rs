@@ -0,0 +1,76 @@
+// Binary-to-text encodings of the rdata of DNSSEC records (RFC 4648).
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE32HEX: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+
+/// encodes bytes in base64, with padding (RFC 4648 §4).
+pub(crate) fn to_base64(b: &[u8]) -> String {
+    let mut s = String::new();
+    for chunk in b.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            match i <= chunk.len() {
+                true => s.push(BASE64[(n >> (18 - 6 * i) & 0x3F) as usize] as char),
+                false => s.push('='),
+            }
+        }
+    }
+    s
+}
+
+/// decodes base64, with or without padding.
+pub(crate) fn from_base64(s: &str) -> Result<Vec<u8>, String> {
+    decode_bits(s.trim_end_matches('='), 6, |c| BASE64.iter().position(|&b| b == c))
+        .ok_or_else(|| "value should be valid base64".to_string())
+}
+
+/// encodes bytes in base32 with the extended hex alphabet, without padding (RFC 4648 §7), as
+/// NSEC3 hashed owner names.
+pub(crate) fn to_base32hex(b: &[u8]) -> String {
+    let mut s = String::new();
+    for chunk in b.chunks(5) {
+        let n = chunk.iter().enumerate().fold(0u64, |n, (i, &b)| n | u64::from(b) << (32 - 8 * i));
+        let chars = (chunk.len() * 8).div_ceil(5);
+        for i in 0..chars {
+            s.push(BASE32HEX[(n >> (35 - 5 * i) & 0x1F) as usize] as char);
+        }
+    }
+    s
+}
+
+/// decodes base32 with the extended hex alphabet, case-insensitively.
+pub(crate) fn from_base32hex(s: &str) -> Result<Vec<u8>, String> {
+    decode_bits(s.trim_end_matches('='), 5, |c| BASE32HEX.iter().position(|&b| b == c.to_ascii_uppercase()))
+        .ok_or_else(|| "value should be valid base32hex".to_string())
+}
+
+pub(crate) fn to_hex(b: &[u8]) -> String {
+    b.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+pub(crate) fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    if !s.len().is_multiple_of(2) {
+        return Err("value should have an even number of hex digits".to_string())
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2).unwrap_or("-"), 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| "value should be valid hex".to_string())
+}
+
+// decode_bits decodes characters of `width` bits each, dropping the trailing bits which do not
+// form a whole byte.
+fn decode_bits(s: &str, width: u32, value: impl Fn(u8) -> Option<usize>) -> Option<Vec<u8>> {
+    let (mut bytes, mut buffer, mut bits) = (vec![], 0u32, 0u32);
+    for c in s.bytes() {
+        buffer = buffer << width | value(c)? as u32;
+        bits += width;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
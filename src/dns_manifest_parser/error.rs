@@ -0,0 +1,89 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use rocket::serde::Serialize;
+use crate::dns_manifest_parser::tokenizer::Token;
+
+// ---------------------------------------- ParseErrorKind -----------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) enum ParseErrorKind {
+    // The zonefile could not be read.
+    Io,
+    // Unbalanced parentheses or quotes, invalid escapes.
+    Syntax,
+    // Unknown or malformed `$` directive.
+    Directive,
+    // A record lacks its owner name, type or some of its rdata.
+    MissingField,
+    // A record has more rdata fields than its type allows.
+    UnexpectedField,
+    UnknownType,
+    // A field could not be parsed, e.g. an integer, an address or a character-string.
+    InvalidValue,
+    // The zone as a whole is invalid, e.g. it has no SOA record or several of them.
+    Zone,
+    // Malformed wire-format data, e.g. a truncated message or a forward compression pointer.
+    Wire,
+}
+
+// ------------------------------------------ ParseError -------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct ParseError {
+    kind: ParseErrorKind,
+    // 1-based position of the offending text; 0 when unknown, e.g. for values received as json.
+    line: usize,
+    column: usize,
+    // offending text.
+    text: String,
+    message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, text: &str, message: String) -> Self {
+        Self{ kind, line: 0, column: 0, text: text.to_string(), message }
+    }
+
+    /// locates the error at `line` and `column`.
+    pub(crate) fn at(self, line: usize, column: usize) -> Self {
+        Self{ line, column, ..self }
+    }
+
+    /// locates the error at `token`, unless it is already located.
+    pub(crate) fn at_token(self, token: &Token) -> Self {
+        match self.line {
+            0 => self.at(token.line, token.column),
+            _ => self,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}, column {}: ", self.line, self.column)?;
+        }
+        write!(f, "{}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, " (`{}`)", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<ParseError> for String {
+    fn from(e: ParseError) -> Self {
+        e.to_string()
+    }
+}
+
+/// joins several errors into a single message, one error per line.
+pub(crate) fn join_errors(errors: &[ParseError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
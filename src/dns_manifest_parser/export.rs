@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use crate::dns_manifest_parser::manifest::Manifest;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::record::RecordData;
+
+// Maximum number of CNAMEs followed to find the addresses of an alias.
+const MAX_CNAME_CHAIN: usize = 8;
+
+// ----------------------------------------- ExportFormat ------------------------------------------
+
+/// ExportFormat is a static file format, for hosts which cannot query the nameserver.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ExportFormat {
+    // `/etc/hosts`: address records, and the CNAMEs pointing to them as aliases.
+    Hosts,
+    // dnsmasq `host-record=`, `address=` and `cname=` lines.
+    Dnsmasq,
+    // Unbound `local-zone:` and `local-data:` stanzas.
+    Unbound,
+}
+
+impl ExportFormat {
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "hosts" => Ok(Self::Hosts),
+            "dnsmasq" => Ok(Self::Dnsmasq),
+            "unbound" => Ok(Self::Unbound),
+            _ => Err(format!("export format should be `hosts`, `dnsmasq` or `unbound`, received: {}", s)),
+        }
+    }
+
+    /// renders a manifest. Records the format cannot express, e.g. wildcards in `/etc/hosts`,
+    /// are written as comments.
+    pub(crate) fn render(&self, manifest: &Manifest) -> String {
+        let origin = manifest.get_origin();
+        let records: Vec<RecordData> = manifest.get_records()
+            .iter()
+            .map(|r| r.resolve(origin))
+            .collect();
+
+        let mut lines = vec![format!(
+            "# generated by betterdns from zone {}, serial {}",
+            origin.get_origin(), manifest.get_soa().get_serial()
+        )];
+        lines.extend(match self {
+            Self::Hosts => hosts(&records),
+            Self::Dnsmasq => dnsmasq(&records),
+            Self::Unbound => {
+                let soa = RecordData::SOA(manifest.get_soa().clone()).resolve(origin);
+                unbound(&origin.get_origin(), manifest.get_ttl().get_ttl(), &soa, &records)
+            }
+        });
+        lines.join("\n") + "\n"
+    }
+}
+
+// hosts writes one line per address, in order of appearance, followed by its names.
+fn hosts(records: &[RecordData]) -> Vec<String> {
+    let (addresses, aliases) = index(records);
+    let mut hosts: Vec<(IpAddr, Vec<&str>)> = vec![];
+    let mut skipped: Vec<String> = vec![];
+
+    for r in records {
+        let ips = match r {
+            RecordData::A(_) | RecordData::AAAA(_) => address(r).into_iter().collect(),
+            RecordData::CNAME(_) => follow(r.get_name(), &addresses, &aliases),
+            _ => continue,
+        };
+        if r.get_name().is_wildcard() || ips.is_empty() {
+            skipped.push(format!("# skipped: {}", r));
+            continue
+        }
+        let host = host_name(r.get_name());
+        for ip in ips {
+            match hosts.iter_mut().find(|(a, _)| *a == ip) {
+                Some((_, names)) if !names.contains(&host) => names.push(host),
+                Some(_) => {}
+                None => hosts.push((ip, vec![host])),
+            }
+        }
+    }
+
+    hosts
+        .into_iter()
+        .map(|(ip, names)| format!("{}\t{}", ip, names.join(" ")))
+        .chain(skipped)
+        .collect()
+}
+
+// dnsmasq writes wildcards as `address=` lines, which also match the parent of the wildcard.
+fn dnsmasq(records: &[RecordData]) -> Vec<String> {
+    records
+        .iter()
+        .filter_map(|r| {
+            let name = r.get_name();
+            match (r, address(r)) {
+                (_, Some(ip)) if name.is_wildcard() => Some(format!(
+                    "address=/{}/{}", host_name(name).trim_start_matches("*."), ip
+                )),
+                (_, Some(ip)) => Some(format!("host-record={},{}", host_name(name), ip)),
+                (RecordData::CNAME(_), _) if name.is_wildcard() => Some(format!("# skipped: {}", r)),
+                (RecordData::CNAME(c), _) => Some(format!(
+                    "cname={},{}", host_name(name), host_name(c.get_value())
+                )),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+// unbound serves the zone as a static local zone: names without local data do not exist.
+fn unbound(origin: &str, ttl: u32, soa: &RecordData, records: &[RecordData]) -> Vec<String> {
+    let mut lines = vec![
+        "server:".to_string(),
+        format!("    local-zone: \"{}\" static", origin),
+    ];
+    for r in std::iter::once(soa).chain(records) {
+        if r.get_name().is_wildcard() {
+            lines.push(format!("    # skipped: {}", r));
+            continue
+        }
+        let data = format!(
+            "{} {} IN {} {}", r.get_name(), r.get_ttl().unwrap_or(ttl), r.get_type(), r.get_rdata()
+        );
+        // quotes of TXT records are kept by enclosing the data in single quotes.
+        let quote = if data.contains('"') { '\'' } else { '"' };
+        lines.push(format!("    local-data: {}{}{}", quote, data, quote));
+    }
+    lines
+}
+
+// index maps the canonical names of the records to their addresses, and aliases to their target.
+fn index(records: &[RecordData]) -> (HashMap<String, Vec<IpAddr>>, HashMap<String, String>) {
+    let mut addresses: HashMap<String, Vec<IpAddr>> = HashMap::new();
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    for r in records {
+        let name = canonical(r.get_name());
+        match (r, address(r)) {
+            (_, Some(ip)) => addresses.entry(name).or_default().push(ip),
+            (RecordData::CNAME(c), _) => { aliases.insert(name, canonical(c.get_value())); }
+            _ => {}
+        }
+    }
+    (addresses, aliases)
+}
+
+// follow returns the addresses of an alias, following a chain of CNAMEs inside the zone.
+fn follow(
+    name: &DomainName,
+    addresses: &HashMap<String, Vec<IpAddr>>,
+    aliases: &HashMap<String, String>
+) -> Vec<IpAddr> {
+    let mut name = canonical(name);
+    for _ in 0..MAX_CNAME_CHAIN {
+        match aliases.get(&name) {
+            Some(target) => name = target.to_string(),
+            None => break,
+        }
+    }
+    addresses.get(&name).cloned().unwrap_or_default()
+}
+
+fn address(record: &RecordData) -> Option<IpAddr> {
+    match record {
+        RecordData::A(r) => Some(IpAddr::V4(*r.get_value())),
+        RecordData::AAAA(r) => Some(IpAddr::V6(*r.get_value())),
+        _ => None,
+    }
+}
+
+// canonical returns the key of an absolute name.
+fn canonical(name: &DomainName) -> String {
+    name.as_str().to_ascii_lowercase()
+}
+
+// host_name strips the root label, as hosts files and dnsmasq expect.
+fn host_name(name: &DomainName) -> &str {
+    name.as_str().trim_end_matches('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExportFormat;
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 7 7200 3600 1209600 3600
+@ IN NS ns1
+ns1 IN A 10.0.0.1
+web 300 IN A 10.0.0.5
+web IN AAAA 2001:db8::5
+www IN CNAME web
+api IN CNAME www
+ext IN CNAME example.org.
+*.dev IN A 10.128.0.50
+@ IN TXT \"v=spf1 -all\"
+";
+
+    fn render(format: &str) -> String {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).expect("manifest should parse");
+        ExportFormat::parse(format).unwrap().render(&manifest)
+    }
+
+    #[test]
+    fn hosts_follow_aliases() {
+        assert_eq!(render("hosts"), "# generated by betterdns from zone example.com., serial 7
+10.0.0.1\tns1.example.com
+10.0.0.5\tweb.example.com www.example.com api.example.com
+2001:db8::5\tweb.example.com www.example.com api.example.com
+# skipped: ext.example.com. IN CNAME example.org.
+# skipped: *.dev.example.com. IN A 10.128.0.50
+");
+    }
+
+    #[test]
+    fn dnsmasq_matches_wildcards_with_addresses() {
+        assert_eq!(render("DNSMASQ"), "# generated by betterdns from zone example.com., serial 7
+host-record=ns1.example.com,10.0.0.1
+host-record=web.example.com,10.0.0.5
+host-record=web.example.com,2001:db8::5
+cname=www.example.com,web.example.com
+cname=api.example.com,www.example.com
+cname=ext.example.com,example.org
+address=/dev.example.com/10.128.0.50
+");
+    }
+
+    #[test]
+    fn unbound_serves_a_static_zone() {
+        let unbound = render("unbound");
+        assert!(unbound.contains("    local-zone: \"example.com.\" static\n"));
+        assert!(unbound.contains("    local-data: \"example.com. 3600 IN SOA ns1.example.com. admin.example.com. 7 7200 3600 1209600 3600\"\n"));
+        assert!(unbound.contains("    local-data: \"web.example.com. 300 IN A 10.0.0.5\"\n"));
+        assert!(unbound.contains("    local-data: 'example.com. 3600 IN TXT \"v=spf1 -all\"'\n"));
+        assert!(unbound.contains("    # skipped: *.dev.example.com. IN A 10.128.0.50\n"));
+    }
+
+    #[test]
+    fn unknown_formats_are_rejected() {
+        assert!(ExportFormat::parse("bind").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use rocket::serde::json;
+use crate::dns_manifest_parser::error::{join_errors, ParseError, ParseErrorKind};
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::record::{OriginRecord, RecordData, TTLRecord};
+use crate::dns_manifest_parser::record::wire::{Decoder, Encoder};
+
+// -------------------------------------------- Format ---------------------------------------------
+
+/// Format is an encoding of a whole manifest.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Format {
+    // master file (RFC 1035 §5).
+    Zone,
+    Json,
+    Yaml,
+    // records in wire format, framed by the SOA record like a zone transfer (RFC 5936 §2.2).
+    Wire,
+}
+
+impl Format {
+    pub(crate) fn encode(&self, manifest: &Manifest) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Zone => Ok(manifest.to_string().into_bytes()),
+            Self::Json => json::to_pretty_string(manifest).map(String::into_bytes).map_err(|e| e.to_string()),
+            Self::Yaml => serde_norway::to_string(manifest).map(String::into_bytes).map_err(|e| e.to_string()),
+            Self::Wire => encode_wire(manifest).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// decodes a manifest. Zonefiles are parsed strictly; the records of other formats are
+    /// validated as if they were received one by one.
+    pub(crate) fn decode(&self, b: &[u8]) -> Result<Manifest, String> {
+        let text = || String::from_utf8(b.to_vec()).map_err(|e| e.to_string());
+        let manifest: Manifest = match self {
+            Self::Zone => return match Manifest::parse(&text()?, ParseMode::Strict) {
+                Ok((manifest, _)) => Ok(manifest),
+                Err(e) => Err(join_errors(&e)),
+            },
+            Self::Json => json::from_str(&text()?).map_err(|e| e.to_string())?,
+            Self::Yaml => serde_norway::from_str(&text()?).map_err(|e| e.to_string())?,
+            Self::Wire => decode_wire(b).map_err(|e| e.to_string())?,
+        };
+        for r in manifest.get_records() {
+            r.validate()?
+        }
+        Ok(manifest)
+    }
+}
+
+fn encode_wire(manifest: &Manifest) -> Result<Vec<u8>, ParseError> {
+    let (origin, ttl) = (manifest.get_origin(), manifest.get_ttl().get_ttl());
+    let soa = RecordData::SOA(manifest.get_soa().clone());
+    let mut encoder = Encoder::new();
+    encoder.record(&soa, origin, ttl)?;
+    for r in manifest.get_records() {
+        encoder.record(r, origin, ttl)?;
+    }
+    encoder.record(&soa, origin, ttl)?;
+    Ok(encoder.finish())
+}
+
+// decode_wire reads a zone starting with its SOA record, whose owner is the origin of the zone
+// and whose TTL is the `$TTL` of the zone. The closing SOA record is optional.
+fn decode_wire(b: &[u8]) -> Result<Manifest, ParseError> {
+    let mut decoder = Decoder::new(b, 0);
+    let mut records: Vec<RecordData> = vec![];
+    while !decoder.is_empty() {
+        records.push(decoder.record()?);
+    }
+
+    let soa = match records.first() {
+        Some(RecordData::SOA(soa)) => soa.clone(),
+        _ => return Err(zone_error("zone should start with a SOA record")),
+    };
+    let ttl = RecordData::SOA(soa.clone()).get_ttl().unwrap_or_default();
+    if records.len() > 1 && records.last().is_some_and(|r| r.to_string() == soa.to_string()) {
+        records.pop();
+    }
+    records.remove(0);
+    if records.iter().any(|r| matches!(r, RecordData::SOA(_))) {
+        return Err(zone_error("zone should contain a single SOA record"))
+    }
+
+    Ok(Manifest::from_parts(
+        OriginRecord::from_name(soa.get_name().as_str()),
+        TTLRecord::from_ttl(ttl),
+        soa,
+        records,
+    ))
+}
+
+fn zone_error(message: &str) -> ParseError {
+    ParseError::new(ParseErrorKind::Zone, "", message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Encoder, Format};
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 7 7200 3600 1209600 3600
+@ IN NS ns1
+ns1 IN A 10.0.0.1
+web 300 IN A 10.0.0.5
+web IN AAAA 2001:db8::5
+www IN CNAME web
+@ IN MX 10 web
+_http._tcp IN SRV 0 5 80 web
+*.dev IN A 10.128.0.50
+@ IN TXT \"v=spf1 -all\" \"a\\200b\"
+";
+
+    fn manifest() -> Manifest {
+        Manifest::parse(ZONE, ParseMode::Strict).expect("manifest should parse").0
+    }
+
+    // records lists the SOA and records of a manifest, leaving its layout out.
+    fn records(manifest: &Manifest) -> Vec<String> {
+        let soa = manifest.get_soa().to_string();
+        std::iter::once(soa).chain(manifest.get_records().iter().map(|r| r.to_string())).collect()
+    }
+
+    #[test]
+    fn manifests_round_trip_through_every_format() {
+        let manifest = manifest();
+        for format in [Format::Zone, Format::Json, Format::Yaml] {
+            let decoded = format.decode(&format.encode(&manifest).unwrap()).unwrap();
+            assert_eq!(records(&decoded), records(&manifest), "{:?}", format);
+        }
+
+        // names are absolute and TTLs explicit in wire format.
+        let encoded = Format::Wire.encode(&manifest).unwrap();
+        let decoded = Format::Wire.decode(&encoded).unwrap();
+        assert_eq!(decoded.get_records().len(), manifest.get_records().len());
+        assert!(decoded.to_string().contains("example.com. 3600 IN TXT \"v=spf1 -all\" \"a\\200b\""));
+        assert_eq!(Format::Wire.encode(&decoded).unwrap(), encoded);
+    }
+
+    #[test]
+    fn imported_records_are_validated() {
+        let json = String::from_utf8(Format::Json.encode(&manifest()).unwrap()).unwrap();
+        let e = Format::Json.decode(json.replace("\"exchange\": \"web\"", "\"exchange\": \"*.web\"").as_bytes());
+        assert!(e.is_err_and(|e| e.contains("exchange should be a host name")));
+
+        let yaml = String::from_utf8(Format::Yaml.encode(&manifest()).unwrap()).unwrap();
+        let e = Format::Yaml.decode(yaml.replace("name: _http._tcp", "name: web").as_bytes());
+        assert!(e.is_err_and(|e| e.contains("_service._proto.name")));
+
+        let e = Format::Zone.decode(ZONE.replace("10.0.0.1", "10.0.0.256").as_bytes());
+        assert!(e.is_err());
+
+        // a zone transfer starts with the SOA record.
+        let manifest = manifest();
+        let mut encoder = Encoder::new();
+        encoder.record(&manifest.get_records()[0], manifest.get_origin(), 3600).unwrap();
+        let e = Format::Wire.decode(&encoder.finish());
+        assert!(e.is_err_and(|e| e.contains("zone should start with a SOA record")));
+    }
+}
@@ -0,0 +1,309 @@
+use std::ops::Range;
+use crate::dns_manifest_parser::record::{RecordData, SOARecord};
+use crate::dns_manifest_parser::tokenizer::{Entry, Token};
+
+// ------------------------------------------- Segment ---------------------------------------------
+
+// A segment is a piece of a zonefile as it was read: one or several physical lines.
+#[derive(Clone, Debug)]
+struct Segment {
+    text: String,
+    kind: SegmentKind,
+    // the segment was read under the `$ORIGIN` and `$TTL` of the manifest, i.e. records written
+    // next to it are read back as they are written.
+    default_scope: bool,
+}
+
+#[derive(Clone, Debug)]
+enum SegmentKind {
+    // comments, blank lines, directives and entries skipped by the parser.
+    Text,
+    // the SOA record as it was read, and the position of its serial in the text.
+    Soa(SOARecord, Option<Range<usize>>),
+    // a record as it was read.
+    Record(RecordData),
+}
+
+// -------------------------------------------- Layout ---------------------------------------------
+
+// Layout keeps the text of a parsed zonefile, so that writing the manifest back preserves the
+// comments, blank lines, ordering and alignment of the entries which did not change.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Layout {
+    segments: Vec<Segment>,
+    // the end of the zonefile is under the `$ORIGIN` and `$TTL` of the manifest.
+    default_scope: bool,
+    // physical lines not yet assigned to a segment.
+    lines: Vec<String>,
+    next_line: usize,
+}
+
+impl Layout {
+    pub(crate) fn new(s: &str) -> Self {
+        Self{
+            segments: vec![],
+            default_scope: true,
+            lines: s.split('\n').map(|l| l.to_string()).collect(),
+            next_line: 0,
+        }
+    }
+
+    pub(crate) fn push_text(&mut self, entry: &Entry, default_scope: bool) {
+        self.push(entry, SegmentKind::Text, default_scope)
+    }
+
+    pub(crate) fn push_record(&mut self, entry: &Entry, record: RecordData, default_scope: bool) {
+        self.push(entry, SegmentKind::Record(record), default_scope)
+    }
+
+    /// `serial` is the token of the serial of the SOA record, if it could be found.
+    pub(crate) fn push_soa(&mut self, entry: &Entry, soa: SOARecord, serial: Option<&Token>, default_scope: bool) {
+        let range = serial.map(|t| {
+            let offset: usize = self.lines[entry.line - 1..t.line - 1]
+                .iter()
+                .map(|l| l.len() + 1)
+                .sum();
+            let column: usize = self.lines[t.line - 1]
+                .chars()
+                .take(t.column - 1)
+                .map(|c| c.len_utf8())
+                .sum();
+            offset + column..offset + column + t.text.len()
+        });
+        self.push(entry, SegmentKind::Soa(soa, range), default_scope)
+    }
+
+    /// assigns the remaining lines of the zonefile.
+    pub(crate) fn finish(&mut self, default_scope: bool) {
+        self.flush_text(self.lines.len(), default_scope);
+        self.default_scope = default_scope;
+        self.lines = vec![];
+    }
+
+    fn push(&mut self, entry: &Entry, kind: SegmentKind, default_scope: bool) {
+        self.flush_text(entry.line - 1, default_scope);
+        let text = self.lines[entry.line - 1..entry.end_line].join("\n");
+        self.segments.push(Segment{ text, kind, default_scope });
+        self.next_line = entry.end_line;
+    }
+
+    // flush_text assigns the lines before `end` to a text segment, e.g. comments and blank lines.
+    fn flush_text(&mut self, end: usize, default_scope: bool) {
+        if self.next_line < end {
+            let text = self.lines[self.next_line..end].join("\n");
+            self.segments.push(Segment{ text, kind: SegmentKind::Text, default_scope });
+            self.next_line = end;
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// renders the zonefile of `soa` and `records`: records read from the zonefile which did not
+    /// change keep their text, a changed record takes the place of the RRset it was read in, and
+    /// new records follow the record preceding them in `records`.
+    /// `directives` are written before new records appended where they would be read in another
+    /// scope, i.e. after a later `$ORIGIN` or `$TTL`.
+    pub(crate) fn render(&self, soa: &SOARecord, records: &[RecordData], directives: &[String]) -> String {
+        let n = self.segments.len();
+        let mut kept = vec![false; n];
+        let mut replaced: Vec<Option<String>> = vec![None; n];
+        let mut after: Vec<Vec<String>> = vec![vec![]; n];
+        let mut tail: Vec<String> = vec![];
+
+        // records which did not change keep their segment.
+        let mut anchors: Vec<Option<usize>> = records
+            .iter()
+            .map(|r| {
+                let s = r.to_string();
+                let i = self.segments.iter().enumerate().position(|(i, seg)| {
+                    !kept[i] && matches!(&seg.kind, SegmentKind::Record(o) if o.to_string() == s)
+                })?;
+                kept[i] = true;
+                Some(i)
+            })
+            .collect();
+
+        let soa_segment = self.segments.iter().position(|seg| matches!(seg.kind, SegmentKind::Soa(..)));
+        let last_in_scope = self.segments.iter().rposition(|seg| {
+            seg.default_scope && !matches!(seg.kind, SegmentKind::Text)
+        });
+        for (j, r) in records.iter().enumerate() {
+            if anchors[j].is_some() {
+                continue
+            }
+
+            // a changed record takes the place of a record of its RRset which is gone.
+            if let Some(i) = self.segments.iter().enumerate().position(|(i, seg)| {
+                !kept[i] && replaced[i].is_none() && matches!(
+                    &seg.kind,
+                    SegmentKind::Record(o) if o.get_name() == r.get_name() && o.get_type() == r.get_type()
+                )
+            }) {
+                replaced[i] = Some(r.to_string());
+                anchors[j] = Some(i);
+                continue
+            }
+
+            // records following a later `$ORIGIN` or `$TTL` are followed by the last record read
+            // in the scope of the manifest instead.
+            let anchor = match j {
+                0 => soa_segment,
+                _ => anchors[j - 1],
+            }.filter(|&i| self.segments[i].default_scope).or(last_in_scope);
+            match anchor {
+                Some(i) => {
+                    after[i].push(r.to_string());
+                    anchors[j] = Some(i);
+                }
+                None => tail.push(r.to_string()),
+            }
+        }
+
+        let mut v: Vec<String> = vec![];
+        // the record or SOA segment written last with its text, whose owner the next line may omit.
+        let mut previous: Option<usize> = None;
+        for (i, seg) in self.segments.iter().enumerate() {
+            match &seg.kind {
+                SegmentKind::Text => v.push(seg.text.to_string()),
+                SegmentKind::Soa(original, serial) => {
+                    v.push(render_soa(&seg.text, original, serial, soa));
+                    previous = (soa.get_name() == original.get_name()).then_some(i);
+                }
+                SegmentKind::Record(original) if kept[i] => {
+                    // a line omitting its owner takes the owner of the line above: write it when
+                    // that line changed, e.g. when the record above was deleted.
+                    match omits_owner(&seg.text) && previous != self.previous_record(i) {
+                        true => v.push(original.to_string()),
+                        false => v.push(seg.text.to_string()),
+                    }
+                    previous = Some(i);
+                }
+                SegmentKind::Record(_) => {
+                    v.extend(replaced[i].clone());
+                    previous = None;
+                }
+            }
+            if !after[i].is_empty() {
+                v.extend(after[i].iter().cloned());
+                previous = None;
+            }
+        }
+
+        let s = v.join("\n");
+        if tail.is_empty() {
+            return s
+        }
+        // new records go before the trailing newline of the zonefile, if any.
+        let (body, newline) = match s.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (s.as_str(), ""),
+        };
+        let mut v = vec![body.to_string()];
+        if !self.default_scope {
+            v.extend(directives.iter().cloned());
+        }
+        v.extend(tail);
+        format!("{}{}", v.join("\n"), newline)
+    }
+
+    // previous_record returns the record or SOA segment read before the segment `i`.
+    fn previous_record(&self, i: usize) -> Option<usize> {
+        self.segments[..i].iter().rposition(|seg| !matches!(seg.kind, SegmentKind::Text))
+    }
+}
+
+// omits_owner returns whether the first line of a record starts with a blank, i.e. omits its owner.
+fn omits_owner(text: &str) -> bool {
+    text.starts_with([' ', '\t'])
+}
+
+// render_soa keeps the text of the SOA record when only its serial changed, e.g. after an
+// increment.
+fn render_soa(text: &str, original: &SOARecord, serial: &Option<Range<usize>>, soa: &SOARecord) -> String {
+    let s = original.to_string();
+    match serial {
+        _ if soa.to_string() == s => text.to_string(),
+        Some(range) if soa.with_serial(original.get_serial()).to_string() == s => {
+            format!("{}{}{}", &text[..range.start], soa.get_serial(), &text[range.end..])
+        }
+        _ => soa.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dns_manifest_parser::manifest::{Manifest, ManifestBuilder, ParseMode};
+    use crate::dns_manifest_parser::name::DomainName;
+    use crate::dns_manifest_parser::record;
+    use crate::dns_manifest_parser::record::RecordData;
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+ns1 IN A 10.0.0.1
+web IN A 10.0.0.5
+    IN TXT \"hello\"
+";
+
+    // record parses a single record of the zone.
+    fn record(s: &str) -> RecordData {
+        let zone = format!("{}{}\n", &ZONE[..ZONE.find("ns1 IN A").unwrap()], s);
+        let (manifest, _) = Manifest::parse(&zone, ParseMode::Strict).expect("record should parse");
+        manifest.get_records()[0].clone()
+    }
+
+    fn rrset(manifest: &str, name: &str, record_type: &str) -> Vec<String> {
+        let (manifest, _) = Manifest::parse(manifest, ParseMode::Strict).expect("manifest should parse");
+        let builder = ManifestBuilder::from(manifest);
+        builder.get_rrset(&DomainName::parse(name).unwrap(), record_type).iter().map(|r| r.to_string()).collect()
+    }
+
+    #[test]
+    fn deleting_the_record_above_a_line_omitting_its_owner_keeps_its_owner() {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).unwrap();
+        let written = ManifestBuilder::from(manifest)
+            .delete_record(&DomainName::parse("web").unwrap(), record::A)
+            .build()
+            .to_string();
+
+        assert_eq!(rrset(&written, "web", record::TXT).len(), 1, "{}", written);
+        assert!(rrset(&written, "ns1", record::TXT).is_empty(), "{}", written);
+    }
+
+    #[test]
+    fn replacing_the_record_above_a_line_omitting_its_owner_keeps_its_owner() {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).unwrap();
+        let written = ManifestBuilder::from(manifest)
+            .update_record(&DomainName::parse("web").unwrap(), record("web IN A 10.0.0.9"))
+            .unwrap()
+            .build()
+            .to_string();
+
+        assert_eq!(rrset(&written, "web", record::TXT).len(), 1, "{}", written);
+        assert_eq!(rrset(&written, "web", record::A), vec!["web IN A 10.0.0.9"], "{}", written);
+    }
+
+    #[test]
+    fn inserting_a_record_above_a_line_omitting_its_owner_keeps_its_owner() {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).unwrap();
+        let written = ManifestBuilder::from(manifest)
+            .add_record(record("mail IN A 10.0.0.25"))
+            .unwrap()
+            .add_record(record("web IN A 10.0.0.6"))
+            .unwrap()
+            .build()
+            .to_string();
+
+        assert_eq!(rrset(&written, "web", record::TXT).len(), 1, "{}", written);
+        assert_eq!(rrset(&written, "web", record::A).len(), 2, "{}", written);
+        assert!(rrset(&written, "mail", record::TXT).is_empty(), "{}", written);
+    }
+
+    #[test]
+    fn unchanged_lines_omitting_their_owner_are_kept() {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).unwrap();
+        assert_eq!(ManifestBuilder::from(manifest).build().to_string(), ZONE);
+    }
+}
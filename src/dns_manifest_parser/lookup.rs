@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use crate::dns_manifest_parser::record::RecordData;
+
+// ------------------------------------------- Answer ----------------------------------------------
+
+/// Answer tells which records of a zone would answer a query for a name and a type, following
+/// the wildcard rules of RFC 4592.
+pub(crate) enum Answer<'a> {
+    // records owned by the name itself, or its CNAME.
+    Exact(Vec<&'a RecordData>),
+    // records of the wildcard of the closest encloser of the name, or its CNAME.
+    Wildcard(Vec<&'a RecordData>),
+    // the name exists, but has no record of this type.
+    NoData,
+    // the name does not exist and no wildcard covers it.
+    NxDomain,
+}
+
+/// returns the names existing in a zone (RFC 4592 §2.2.2): the canonical owner names and the
+/// empty non-terminals between them and the origin, e.g. `dev.example.com.` for
+/// `*.dev.example.com.`.
+pub(crate) fn existing_names(owners: impl Iterator<Item = String>, origin: &str) -> HashSet<String> {
+    let mut names: HashSet<String> = HashSet::new();
+    for owner in owners {
+        let mut name = owner.as_str();
+        while names.insert(name.to_string()) && name != origin {
+            name = match parent(name) {
+                Some(parent) if is_subdomain(parent, origin) => parent,
+                _ => break,
+            };
+        }
+    }
+    names
+}
+
+/// returns the closest encloser of `name` (RFC 4592 §3.3.1), i.e. its longest existing ancestor.
+pub(crate) fn closest_encloser<'a>(name: &'a str, names: &HashSet<String>) -> Option<&'a str> {
+    let mut name = parent(name)?;
+    while !names.contains(name) {
+        name = parent(name)?;
+    }
+    Some(name)
+}
+
+/// returns the source of synthesis of a name missing from the zone, i.e. the wildcard of its
+/// closest encloser, if it exists.
+pub(crate) fn source_of_synthesis(name: &str, names: &HashSet<String>) -> Option<String> {
+    let wildcard = format!("*.{}", closest_encloser(name, names)?);
+    names.contains(&wildcard).then_some(wildcard)
+}
+
+// parent strips the leftmost label of an absolute name; dots escaped as `\.` belong to labels.
+fn parent(name: &str) -> Option<&str> {
+    let mut chars = name.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => { chars.next(); }
+            '.' if i + 1 < name.len() => return Some(&name[i + 1..]),
+            '.' => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+// is_subdomain tells whether `name` is `origin` or one of its subdomains, e.g. not
+// `badexample.com.` for `example.com.`.
+fn is_subdomain(name: &str, origin: &str) -> bool {
+    let mut name = Some(name);
+    while let Some(n) = name {
+        if n == origin {
+            return true
+        }
+        name = parent(n).filter(|p| p.len() >= origin.len());
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{existing_names, parent};
+    use crate::dns_manifest_parser::lookup::Answer;
+    use crate::dns_manifest_parser::manifest::{Manifest, ManifestBuilder, ParseMode};
+    use crate::dns_manifest_parser::name::DomainName;
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+*.dev IN A 10.128.0.50
+foo.dev IN TXT \"foo\"
+a.b.staging IN A 10.0.0.1
+*.staging IN A 10.0.0.2
+*.alias IN CNAME web
+web IN A 10.0.0.5
+";
+
+    fn lookup(name: &str, _type: &str) -> String {
+        let builder = ManifestBuilder::from(Manifest::parse(ZONE, ParseMode::Strict).unwrap().0);
+        match builder.lookup(&DomainName::parse(name).unwrap(), _type) {
+            Answer::Exact(records) => format!("exact {}", records[0]),
+            Answer::Wildcard(records) => format!("wildcard {}", records[0]),
+            Answer::NoData => "no data".to_string(),
+            Answer::NxDomain => "nxdomain".to_string(),
+        }
+    }
+
+    #[test]
+    fn wildcards_answer_missing_names_only() {
+        assert_eq!(lookup("pr-42.dev", "A"), "wildcard *.dev IN A 10.128.0.50");
+        assert_eq!(lookup("a.pr-42.dev.example.com.", "A"), "wildcard *.dev IN A 10.128.0.50");
+        assert_eq!(lookup("pr-42.dev", "AAAA"), "no data");
+        // an existing name is not covered by the wildcard, even without records of the type.
+        assert_eq!(lookup("foo.dev", "A"), "no data");
+        assert_eq!(lookup("foo.dev", "TXT"), "exact foo.dev IN TXT \"foo\"");
+        assert_eq!(lookup("*.dev", "A"), "exact *.dev IN A 10.128.0.50");
+        assert_eq!(lookup("dev", "A"), "no data");
+        assert_eq!(lookup("prod", "A"), "nxdomain");
+        // a wildcard CNAME answers queries of any type.
+        assert_eq!(lookup("x.alias", "MX"), "wildcard *.alias IN CNAME web");
+    }
+
+    #[test]
+    fn empty_non_terminals_block_wildcards() {
+        // `b.staging` exists as an empty non-terminal: it is the closest encloser of `c.b.staging`.
+        assert_eq!(lookup("b.staging", "A"), "no data");
+        assert_eq!(lookup("c.b.staging", "A"), "nxdomain");
+        assert_eq!(lookup("c.staging", "A"), "wildcard *.staging IN A 10.0.0.2");
+    }
+
+    #[test]
+    fn existing_names_stop_at_the_origin() {
+        let owners = ["a.b.example.com.", "badexample.com.", "x.badexample.com."].map(String::from);
+        let mut names: Vec<String> = existing_names(owners.into_iter(), "example.com.").into_iter().collect();
+        names.sort();
+        assert_eq!(names, ["a.b.example.com.", "b.example.com.", "badexample.com.", "example.com.", "x.badexample.com."]);
+    }
+
+    #[test]
+    fn escaped_dots_belong_to_labels() {
+        assert_eq!(parent("a\\.b.example.com."), Some("example.com."));
+        assert_eq!(parent("a\\\\.b.example.com."), Some("b.example.com."));
+        assert_eq!(parent("com."), None);
+        let names = existing_names(["first\\.last.example.com.".to_string()].into_iter(), "example.com.");
+        assert!(!names.contains("last.example.com."));
+        assert!(names.contains("example.com."));
+    }
+}
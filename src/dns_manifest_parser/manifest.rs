@@ -1,12 +1,26 @@
 use std::fmt::{Display, Formatter};
 use std::fs;
+use crate::dns_manifest_parser::record;
 use crate::dns_manifest_parser::record::{OriginRecord, RecordData, SOARecord, TTLRecord};
+use crate::dns_manifest_parser::diff;
+use crate::dns_manifest_parser::diff::Diff;
+use crate::dns_manifest_parser::layout::Layout;
+use crate::dns_manifest_parser::lookup;
+use crate::dns_manifest_parser::lookup::Answer;
+use crate::dns_manifest_parser::name::{fqdn, DomainName};
+use crate::dns_manifest_parser::serial;
+use crate::dns_manifest_parser::serial::SerialPolicy;
+use crate::dns_manifest_parser::error::{join_errors, ParseError, ParseErrorKind};
+use crate::dns_manifest_parser::tokenizer::{Entry, Token, tokenize};
+use crate::dns_manifest_parser::validator;
 use rocket::serde::{Deserialize, Serialize};
 
 // --------------------------------------- ManifestBuilder -----------------------------------------
 
 pub(crate) struct ManifestBuilder {
-    manifest: Manifest
+    manifest: Manifest,
+    // records of the manifest as loaded, to tell the errors added by changes from the others.
+    loaded: Vec<RecordData>,
 }
 
 impl ManifestBuilder {
@@ -14,61 +28,203 @@ impl ManifestBuilder {
         std::mem::replace(&mut self.manifest, Manifest::new())
     }
 
-    pub(crate) fn _from(manifest: Manifest) -> Self{
-        Self{ manifest }
+    pub(crate) fn from(manifest: Manifest) -> Self{
+        Self{ loaded: manifest.records.clone(), manifest }
     }
 
+    /// loads the manifest at `filepath`, refusing zonefiles with any error: writing back a
+    /// manifest parsed leniently would silently delete its invalid records.
     pub(crate) fn from_path(filepath: &str) -> Result<Self, String>{
-        match Manifest::from_path(filepath) {
-            Ok(manifest) => Ok(Self{ manifest}),
-            Err(e) => Err(e)
+        match Manifest::from_path(filepath, ParseMode::Strict) {
+            Ok((manifest, _)) => Ok(Self::from(manifest)),
+            Err(e) => Err(join_errors(&e))
         }
     }
 
-    pub(crate) fn get_record_by(&mut self, name: &str, _type: &str) -> Option<(usize, &mut RecordData)> {
-        if let Some(i) = self.manifest.records
+    /// returns the RRset of `name` and `_type`, i.e. all the records sharing this owner name
+    /// and type.
+    pub(crate) fn get_rrset(&self, name: &DomainName, _type: &str) -> Vec<&RecordData> {
+        self.manifest.records
             .iter()
-            .position(|r|{
-                name == r.get_name() && _type == r.get_type()
-            }) {
-            return Some((i, &mut self.manifest.records[i]))
-        }
-        None
+            .filter(|r| self.in_rrset(r, name, _type))
+            .collect()
     }
 
-    pub(crate) fn add_record(&mut self, record: RecordData) -> &mut Self {
-        if let Some((_, matched_record)) = self.get_record_by(&record.get_name(), record.get_type()) {
-            let _ = std::mem::replace(matched_record, record);
+    /// returns the records which would answer a query for `name` and `_type`: the RRset of the
+    /// name, or the one of the wildcard covering it (RFC 4592). A CNAME answers queries of any type.
+    pub(crate) fn lookup(&self, name: &DomainName, _type: &str) -> Answer<'_> {
+        let origin = &self.manifest.origin;
+        if !name.is_in_zone(origin) {
+            return Answer::NxDomain
+        }
+
+        let owners = std::iter::once(self.manifest.soa.get_name())
+            .chain(self.manifest.records.iter().map(|r| r.get_name()))
+            .map(|n| n.to_canonical(origin));
+        let names = lookup::existing_names(owners, &origin.get_origin().to_ascii_lowercase());
+
+        let node = name.to_canonical(origin);
+        if names.contains(&node) {
+            return match self.answer_at(&node, _type) {
+                Some(records) => Answer::Exact(records),
+                None => Answer::NoData,
+            }
         }
-        else {
+        match lookup::source_of_synthesis(&node, &names) {
+            Some(wildcard) => match self.answer_at(&wildcard, _type) {
+                Some(records) => Answer::Wildcard(records),
+                None => Answer::NoData,
+            },
+            None => Answer::NxDomain,
+        }
+    }
+
+    fn answer_at(&self, node: &str, _type: &str) -> Option<Vec<&RecordData>> {
+        let name = DomainName::new_unchecked(node);
+        [_type, record::CNAME]
+            .iter()
+            .map(|t| self.get_rrset(&name, t))
+            .find(|rrset| !rrset.is_empty())
+    }
+
+    pub(crate) fn list_records(&self, _type: &str) -> Vec<&RecordData> {
+        self.manifest.records
+            .iter()
+            .filter(|r| _type == r.get_type())
+            .collect()
+    }
+
+    /// adds a record to its RRset. A record of the RRset with the same rdata is replaced, e.g. to
+    /// change its TTL. A CNAME RRset holds a single record (RFC 2181 §10.1), it is replaced.
+    pub(crate) fn add_record(&mut self, record: RecordData) -> Result<&mut Self, String> {
+        self.check_cname_conflict(&record)?;
+
+        let (name, _type) = (record.get_name(), record.get_type());
+        let records = &self.manifest.records;
+        if let Some(i) = records.iter().position(|r| {
+            self.in_rrset(r, name, _type) && (_type == record::CNAME || r.has_same_rdata(&record, &self.manifest.origin))
+        }) {
+            self.manifest.records[i] = record;
+        } else if let Some(i) = records.iter().rposition(|r| self.in_rrset(r, name, _type)) {
+            // keeps the records of a RRset next to each other.
+            self.manifest.records.insert(i + 1, record);
+        } else {
             self.manifest.records.push(record);
         }
-        self
+        Ok(self)
     }
 
-    pub(crate) fn update_record(&mut self, name: &str, record: RecordData) -> &mut Self {
-        if let Some((_, matched_record)) = self.get_record_by(name, record.get_type()) {
-            let _ = std::mem::replace(matched_record, record);
+    /// a CNAME cannot coexist with any other data at the same owner name (RFC 1034 §3.6.2).
+    fn check_cname_conflict(&self, record: &RecordData) -> Result<(), String> {
+        let (name, origin) = (record.get_name(), &self.manifest.origin);
+        let is_cname = record.get_type() == record::CNAME;
+        if is_cname && name.matches(self.manifest.soa.get_name(), origin) {
+            return Err(format!("CNAME `{}` conflicts with the SOA record of the zone", name))
+        }
+
+        match self.manifest.records.iter().find(|r| {
+            r.get_name().matches(name, origin)
+                && r.get_type() != record.get_type()
+                && (is_cname || r.get_type() == record::CNAME)
+        }) {
+            Some(r) => Err(format!(
+                "{} `{}` conflicts with existing {} record at the same name; \
+                a CNAME cannot coexist with other data",
+                record.get_type(), name, r.get_type()
+            )),
+            None => Ok(())
         }
+    }
+
+    /// replaces the whole RRset of `name` by `record`. The RRset must exist and `record` must be
+    /// owned by `name`.
+    pub(crate) fn update_record(&mut self, name: &DomainName, record: RecordData) -> Result<&mut Self, String> {
+        if !record.get_name().matches(name, &self.manifest.origin) {
+            return Err(format!("name of the record should be `{}`, received: {}", name, record.get_name()))
+        }
+        self.check_cname_conflict(&record)?;
+
+        let _type = record.get_type().to_string();
+        if let Some(i) = self.manifest.records.iter().position(|r| self.in_rrset(r, name, &_type)) {
+            let mut j = 0;
+            self.manifest.records.retain(|r| {
+                j += 1;
+                j == i + 1 || !in_rrset(r, name, &_type, &self.manifest.origin)
+            });
+            self.manifest.records[i] = record;
+            return Ok(self)
+        }
+        Err(format!("no {} record found for `{}`", _type, name))
+    }
+
+    /// deletes the whole RRset of `name` and `record_type`.
+    pub(crate) fn delete_record(&mut self, name: &DomainName, record_type: &str) -> &mut Self {
+        let origin = &self.manifest.origin;
+        self.manifest.records.retain(|r| !in_rrset(r, name, record_type, origin));
         self
     }
 
-    pub(crate) fn delete_record(&mut self, name: &str, record_type: &str) -> &mut Self {
-        if let Some((i, _)) = self.get_record_by(name, record_type) {
-            self.manifest.records.remove(i);
+    /// deletes a single record from its RRset, leaving the other values of the RRset untouched.
+    /// Values are compared as typed data, e.g. the MX exchange `relay-1` is `relay-1.<origin>`.
+    pub(crate) fn delete_record_value(&mut self, record: &RecordData) -> Result<&mut Self, String> {
+        let (name, _type, origin) = (record.get_name(), record.get_type(), &self.manifest.origin);
+        let count = self.manifest.records.len();
+        self.manifest.records.retain(|r| !(in_rrset(r, name, _type, origin) && r.has_same_rdata(record, origin)));
+        if self.manifest.records.len() == count {
+            return Err(format!("no {} record `{}` found for `{}`", _type, record.get_rdata(), name))
+        }
+        Ok(self)
+    }
+
+    /// fails when the changes made to the records add errors to the manifest, e.g. a record out
+    /// of the zone. Errors it had when loaded, e.g. written by hand, do not block other changes.
+    pub(crate) fn check(&mut self) -> Result<&mut Self, String> {
+        let m = &self.manifest;
+        let loaded = Manifest::from_parts(m.origin.clone(), m.ttl.clone(), m.soa.clone(), self.loaded.clone());
+        let errors: Vec<String> = validator::added_errors(&loaded, m).iter().map(|d| d.to_string()).collect();
+        match errors.is_empty() {
+            true => Ok(self),
+            false => Err(errors.join("\n")),
+        }
+    }
+
+    /// moves the serial of the SOA record to `serial` if it comes after it, e.g. so that an
+    /// imported manifest does not go back in time.
+    pub(crate) fn catch_up(&mut self, serial: u32) -> &mut Self {
+        let soa = &mut self.manifest.soa;
+        if serial::is_after(serial, soa.get_serial()) {
+            *soa = soa.with_serial(serial)
         }
         self
     }
 
     /// increments the version of the SOA record.
-    pub(crate) fn increment(&mut self) -> &mut Self {
-        self.manifest.soa.increment();
+    pub(crate) fn increment(&mut self, policy: SerialPolicy) -> &mut Self {
+        self.manifest.soa.increment(policy);
         self
     }
+
+    fn in_rrset(&self, record: &RecordData, name: &DomainName, _type: &str) -> bool {
+        in_rrset(record, name, _type, &self.manifest.origin)
+    }
+}
+
+// in_rrset tells whether `record` belongs to the RRset of `name` and `_type`; relative and
+// absolute names of the same node, in any case, share their RRsets.
+fn in_rrset(record: &RecordData, name: &DomainName, _type: &str, origin: &OriginRecord) -> bool {
+    _type == record.get_type() && name.matches(record.get_name(), origin)
 }
 
 // ------------------------------------------- Manifest --------------------------------------------
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ParseMode {
+    // any invalid entry fails the whole parse.
+    Strict,
+    // invalid entries are skipped and reported as diagnostics.
+    Lenient,
+}
+
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
@@ -77,64 +233,72 @@ pub(crate) struct Manifest {
     ttl: TTLRecord,
     soa: SOARecord,
     // list of all records
-    records: Vec<RecordData>
+    records: Vec<RecordData>,
+    // text of the zonefile the manifest was read from, if any.
+    #[serde(skip)]
+    layout: Layout,
 }
 
 impl Manifest {
-    fn from_str(s: &str) -> Result<Self, String> {
-        let s = clean_str(s);
-        let lines: Vec<&str> = s.split("\n").collect();
-
-        // Parses Origin
-        let origin = OriginRecord::from_str(lines[0])?;
-
-        // Parses ttl
-        let ttl = TTLRecord::from_str(lines[1]);
-
-        // Parses soa
-        let soa: SOARecord;
-        match RecordData::from_str(lines[2]) {
-            Ok(rec) => {
-                match rec {
-                    RecordData::SOA(r) => soa = r,
-                    RecordData::A(r) => {
-                        return Err(format!("expected SOA, received A: {}", r.to_string()))
-                    }
-                    RecordData::NS(r) => {
-                        return Err(format!("expected SOA, received NS: {}", r.to_string()))
-                    }
-                }
-            }
-            Err(e) => return Err(format!("expected SOA; {}", e))
+    /// parses a zonefile. In strict mode any diagnostic fails the parse; in lenient mode
+    /// invalid entries are skipped and returned as diagnostics along with the manifest.
+    /// A zone without SOA record or origin fails in both modes.
+    pub(crate) fn parse(s: &str, mode: ParseMode) -> Result<(Self, Vec<ParseError>), Vec<ParseError>> {
+        let mut parser = ZoneParser::new(s);
+        let (entries, errors) = tokenize(s);
+        parser.diagnostics.extend(errors);
+        for entry in entries.iter() {
+            parser.parse_entry(entry);
         }
 
-        // Parses records from other lines
-        let mut records: Vec<RecordData> = Vec::new();
-        for line in lines[3..].iter() {
-            match RecordData::from_str(line) {
-                Ok(rec) => records.push(rec),
-                Err(e) => println!("{}", e)
-            }
+        let (manifest, diagnostics) = parser.finish()?;
+        match mode {
+            ParseMode::Strict if !diagnostics.is_empty() => Err(diagnostics),
+            _ => Ok((manifest, diagnostics)),
         }
+    }
 
-        Ok(Self{
-            origin,
-            ttl,
-            soa,
-            records,
-        })
+    fn _from_str(s: &str) -> Result<Self, Vec<ParseError>> {
+        Self::parse(s, ParseMode::Strict).map(|(manifest, _)| manifest)
+    }
+
+    pub(crate) fn from_parts(
+        origin: OriginRecord,
+        ttl: TTLRecord,
+        soa: SOARecord,
+        records: Vec<RecordData>
+    ) -> Self {
+        Self{ origin, ttl, soa, records, layout: Layout::default() }
     }
 
-    pub(crate) fn from_path(filepath: &str) -> Result<Self, String> {
-        let s = fs::read_to_string(filepath).unwrap();
-        Self::from_str(&s)
+    pub(crate) fn get_origin(&self) -> &OriginRecord { &self.origin }
+
+    pub(crate) fn get_ttl(&self) -> &TTLRecord { &self.ttl }
+
+    pub(crate) fn get_soa(&self) -> &SOARecord { &self.soa }
+
+    pub(crate) fn get_records(&self) -> &Vec<RecordData> { &self.records }
+
+    /// lists the changes from this manifest to `to`.
+    pub(crate) fn diff(&self, to: &Manifest) -> Diff {
+        diff::diff(self, to)
     }
 
-    fn _from_bytes(b: Vec<u8>) -> Result<Manifest, String> {
+    pub(crate) fn from_path(
+        filepath: &str,
+        mode: ParseMode
+    ) -> Result<(Self, Vec<ParseError>), Vec<ParseError>> {
+        match fs::read_to_string(filepath) {
+            Ok(s) => Self::parse(&s, mode),
+            Err(e) => Err(vec![ParseError::new(ParseErrorKind::Io, filepath, e.to_string())])
+        }
+    }
+
+    fn _from_bytes(b: Vec<u8>) -> Result<Manifest, Vec<ParseError>> {
         let result = String::from_utf8(b);
         match result {
-            Ok(s) => Self::from_str(&s),
-            Err(e) => Err(e.to_string())
+            Ok(s) => Self::_from_str(&s),
+            Err(e) => Err(vec![ParseError::new(ParseErrorKind::Syntax, "", e.to_string())])
         }
     }
 
@@ -148,12 +312,18 @@ impl Manifest {
             ttl: TTLRecord::new(),
             soa: SOARecord::new(),
             records: vec![],
+            layout: Layout::default(),
         }
     }
 }
 
 impl Display for Manifest {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.layout.is_empty() {
+            let directives = [self.origin.to_string(), self.ttl.to_string()];
+            return write!(f, "{}", self.layout.render(&self.soa, &self.records, &directives))
+        }
+
         let mut v = vec!(
             self.origin.to_string(),
             self.ttl.to_string(),
@@ -172,6 +342,405 @@ impl Display for Manifest {
     }
 }
 
-fn clean_str(s: &str) -> String {
-    s.replace("\t", " ")
-}
\ No newline at end of file
+// ------------------------------------------ ZoneParser -------------------------------------------
+
+const DEFAULT_CLASS: &str = "IN";
+const CLASSES: [&str; 4] = ["IN", "CS", "CH", "HS"];
+
+// ZoneParser holds the state of a master file (RFC 1035 §5.1) while its entries are parsed:
+// directives may appear in any order and records inherit the owner and class of previous ones.
+// Invalid entries are skipped and collected as diagnostics.
+struct ZoneParser {
+    // the first `$ORIGIN`, i.e. the origin of the manifest.
+    origin: Option<String>,
+    // the origin relative names are currently resolved against.
+    current_origin: Option<String>,
+    // the first `$TTL`, i.e. the TTL of the manifest.
+    ttl: Option<u32>,
+    // the `$TTL` records without an explicit TTL currently default to.
+    current_ttl: Option<u32>,
+    soa: Option<SOARecord>,
+    records: Vec<RecordData>,
+    last_owner: Option<String>,
+    last_class: String,
+    diagnostics: Vec<ParseError>,
+    layout: Layout,
+}
+
+impl ZoneParser {
+    fn new(s: &str) -> Self {
+        Self{
+            origin: None,
+            current_origin: None,
+            ttl: None,
+            current_ttl: None,
+            soa: None,
+            records: vec![],
+            last_owner: None,
+            last_class: DEFAULT_CLASS.to_string(),
+            diagnostics: vec![],
+            layout: Layout::new(s),
+        }
+    }
+
+    fn parse_entry(&mut self, entry: &Entry) {
+        if let Err(e) = self.try_parse_entry(entry) {
+            // skipped entries are written back as they were read.
+            self.layout.push_text(entry, self.default_scope());
+            self.diagnostics.push(e.at_token(&Token::new("", entry.line, 1)))
+        }
+    }
+
+    // errors are located at their token when known, and at the beginning of the entry otherwise.
+    fn try_parse_entry(&mut self, entry: &Entry) -> Result<(), ParseError> {
+        let v = &entry.tokens;
+        if !entry.owner_omitted && v[0].text.starts_with('$') {
+            self.parse_directive(v)?;
+            self.layout.push_text(entry, self.default_scope());
+            return Ok(())
+        }
+
+        match self.parse_record(entry, v)? {
+            RecordData::SOA(r) => match self.soa {
+                None => {
+                    // the serial is the third rdata field; class and TTL are never named `SOA`.
+                    let start = usize::from(!entry.owner_omitted);
+                    let serial = v[start..]
+                        .iter()
+                        .position(|t| t.text.eq_ignore_ascii_case(record::SOA))
+                        .and_then(|i| v.get(start + i + 3));
+                    self.layout.push_soa(entry, r.clone(), serial, self.default_scope());
+                    self.soa = Some(r)
+                }
+                Some(_) => return Err(ParseError::new(
+                    ParseErrorKind::Zone,
+                    &r.to_string(),
+                    "zonefile should contain a single SOA record, skipping".to_string()
+                ))
+            },
+            r => {
+                self.layout.push_record(entry, r.clone(), self.default_scope());
+                self.records.push(r)
+            }
+        }
+        Ok(())
+    }
+
+    // default_scope tells whether names and TTLs are currently read as the manifest writes them.
+    fn default_scope(&self) -> bool {
+        self.current_origin == self.origin && self.current_ttl == self.ttl
+    }
+
+    fn parse_directive(&mut self, v: &[Token]) -> Result<(), ParseError> {
+        let directive = v[0].text.to_uppercase();
+        match (directive.as_str(), v.len()) {
+            (record::ORIGIN, 2) => {
+                let origin = match &self.current_origin {
+                    Some(current) => fqdn(&v[1].text, current),
+                    None => v[1].text.to_string(),
+                };
+                if !origin.ends_with('.') {
+                    return Err(ParseError::new(
+                        ParseErrorKind::Directive,
+                        &v[1].text,
+                        format!("{} should be an absolute name", record::ORIGIN)
+                    ).at_token(&v[1]))
+                }
+                self.origin.get_or_insert(origin.to_string());
+                self.current_origin = Some(origin);
+                Ok(())
+            }
+            (record::TTL, 2) => {
+                let ttl = record::parse_ttl(&v[1].text).map_err(|e| e.at_token(&v[1]))?;
+                self.ttl.get_or_insert(ttl);
+                self.current_ttl = Some(ttl);
+                Ok(())
+            }
+            (record::ORIGIN | record::TTL, _) => Err(ParseError::new(
+                ParseErrorKind::Directive,
+                &v[0].text,
+                format!("{} should have exactly one argument, received: {}", v[0].text, v.len() - 1)
+            ).at_token(&v[0])),
+            _ => Err(ParseError::new(
+                ParseErrorKind::Directive,
+                &v[0].text,
+                "unsupported directive".to_string()
+            ).at_token(&v[0]))
+        }
+    }
+
+    // parse_record normalizes `[<owner>] [<ttl>] [<class>] <type> <rdata>...` (class and ttl in
+    // any order) into `<owner> <class> <type> <rdata>...` before handing it to `RecordData`.
+    fn parse_record(&mut self, entry: &Entry, v: &[Token]) -> Result<RecordData, ParseError> {
+        let (owner, mut rest) = match entry.owner_omitted {
+            true => match &self.last_owner {
+                Some(owner) => (Token::new(owner, entry.line, 1), v),
+                None => return Err(ParseError::new(
+                    ParseErrorKind::MissingField,
+                    "",
+                    "first record of the zonefile should have an owner name".to_string()
+                ))
+            },
+            false => (Token::new(&self.resolve_owner(&v[0].text), v[0].line, v[0].column), &v[1..]),
+        };
+
+        let (mut class, mut ttl): (Option<Token>, Option<u32>) = (None, None);
+        while let Some(t) = rest.first() {
+            if class.is_none() && CLASSES.contains(&t.text.to_uppercase().as_str()) {
+                class = Some(Token::new(&t.text.to_uppercase(), t.line, t.column));
+            } else if ttl.is_none() && t.text.starts_with(|c: char| c.is_ascii_digit()) {
+                ttl = Some(record::parse_ttl(&t.text).map_err(|e| e.at_token(t))?);
+            } else {
+                break
+            }
+            rest = &rest[1..];
+        }
+        if rest.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::MissingField,
+                &owner.text,
+                "record should have a type".to_string()
+            ))
+        }
+
+        let class = class.unwrap_or_else(|| Token::new(&self.last_class, rest[0].line, rest[0].column));
+        self.last_owner = Some(owner.text.to_string());
+        self.last_class = class.text.to_string();
+
+        // a later `$TTL` is kept on its records, since only the first one is written back.
+        let ttl = ttl.or(match self.current_ttl {
+            Some(current) if Some(current) != self.ttl => Some(current),
+            _ => None
+        });
+
+        let mut tokens = vec![owner, class];
+        tokens.extend_from_slice(rest);
+        // names of the rdata are relative to the current origin too, e.g. after
+        // `$ORIGIN sub.example.com.` the target of `www CNAME web` is `web.sub.example.com.`.
+        RecordData::from_tokens(&tokens, ttl).map(|record| match &self.current_origin {
+            Some(current) if Some(current) != self.origin.as_ref() => record.resolve(&OriginRecord::from_name(current)),
+            _ => record,
+        })
+    }
+
+    // resolve_owner keeps names relative to the manifest origin as written, and makes names
+    // relative to a later `$ORIGIN` absolute.
+    fn resolve_owner(&self, name: &str) -> String {
+        match &self.current_origin {
+            Some(current) if Some(current) != self.origin.as_ref() => fqdn(name, current),
+            _ => name.to_string(),
+        }
+    }
+
+    // finish builds the manifest, failing with all diagnostics when the zone itself is unusable.
+    fn finish(mut self) -> Result<(Manifest, Vec<ParseError>), Vec<ParseError>> {
+        let mut layout = std::mem::take(&mut self.layout);
+        layout.finish(self.default_scope());
+
+        let soa = match self.soa.take() {
+            Some(soa) => soa,
+            None => return Err(self.fail("zonefile should contain a SOA record"))
+        };
+
+        let origin = match self.origin.take() {
+            Some(origin) => origin,
+            None if soa.get_name().is_absolute() => soa.get_name().to_string(),
+            None => return Err(self.fail("zonefile should contain ORIGIN"))
+        };
+
+        let ttl = match self.ttl {
+            Some(ttl) => ttl,
+            None => {
+                self.diagnostics.push(ParseError::new(
+                    ParseErrorKind::Zone,
+                    "",
+                    format!("zonefile should contain TTL, defaulting to {}", record::DEFAULT_TTL)
+                ));
+                record::DEFAULT_TTL
+            }
+        };
+
+        let manifest = Manifest{
+            origin: OriginRecord::from_name(&origin),
+            ttl: TTLRecord::from_ttl(ttl),
+            soa,
+            records: self.records,
+            layout,
+        };
+        Ok((manifest, self.diagnostics))
+    }
+
+    fn fail(mut self, message: &str) -> Vec<ParseError> {
+        self.diagnostics.push(ParseError::new(ParseErrorKind::Zone, "", message.to_string()));
+        self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, ManifestBuilder, ParseMode};
+    use crate::dns_manifest_parser::name::DomainName;
+    use crate::dns_manifest_parser::record;
+    use crate::dns_manifest_parser::record::RecordData;
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+web IN A 10.0.0.5
+api IN A 10.0.0.6
+alias IN CNAME web
+";
+
+    fn builder() -> ManifestBuilder {
+        ManifestBuilder::from(Manifest::parse(ZONE, ParseMode::Strict).unwrap().0)
+    }
+
+    fn record(s: &str) -> RecordData {
+        let zone = format!("{}{}\n", &ZONE[..ZONE.find("web").unwrap()], s);
+        Manifest::parse(&zone, ParseMode::Strict).unwrap().0.get_records()[0].clone()
+    }
+
+    #[test]
+    fn update_rejects_a_record_of_another_name() {
+        let e = builder().update_record(&DomainName::parse("api").unwrap(), record("alias IN A 10.0.0.7")).err();
+        assert!(e.is_some_and(|e| e.contains("name of the record should be")));
+    }
+
+    #[test]
+    fn update_rejects_data_next_to_a_cname() {
+        let e = builder().update_record(&DomainName::parse("alias").unwrap(), record("alias IN A 10.0.0.7")).err();
+        assert!(e.is_some_and(|e| e.contains("a CNAME cannot coexist with other data")));
+    }
+
+    #[test]
+    fn update_replaces_the_rrset_of_its_name() {
+        let b = &mut builder();
+        b.update_record(&DomainName::parse("API.example.com.").unwrap(), record("api IN A 10.0.0.7")).unwrap();
+        let rrset: Vec<String> = b.get_rrset(&DomainName::parse("api").unwrap(), "A").iter().map(|r| r.to_string()).collect();
+        assert_eq!(rrset, vec!["api IN A 10.0.0.7"]);
+    }
+
+    #[test]
+    fn update_rejects_a_missing_rrset() {
+        let e = builder().update_record(&DomainName::parse("new").unwrap(), record("new IN A 10.0.0.7")).err();
+        assert!(e.is_some_and(|e| e.contains("no A record found for `new`")));
+    }
+
+    #[test]
+    fn values_are_deleted_by_typed_rdata() {
+        let b = &mut builder();
+        let alias = &DomainName::parse("alias").unwrap();
+        let value = RecordData::from_rdata(alias, record::CNAME, "WEB.example.com.").unwrap();
+        b.delete_record_value(&value).unwrap();
+        assert!(b.get_rrset(alias, record::CNAME).is_empty());
+
+        let api = &DomainName::parse("api.example.com.").unwrap();
+        let value = RecordData::from_rdata(api, record::A, "10.0.0.06").map(|_| ());
+        assert!(value.is_err(), "values are parsed with the parser of their type");
+        let value = RecordData::from_rdata(api, record::A, " 10.0.0.6 ").unwrap();
+        b.delete_record_value(&value).unwrap();
+        assert!(b.get_rrset(api, record::A).is_empty());
+    }
+
+    #[test]
+    fn deleting_a_missing_value_fails() {
+        let web = &DomainName::parse("web").unwrap();
+        let value = RecordData::from_rdata(web, record::A, "10.0.0.9").unwrap();
+        let e = builder().delete_record_value(&value).err();
+        assert!(e.is_some_and(|e| e.contains("no A record `10.0.0.9` found for `web`")));
+        let value = RecordData::from_rdata(web, record::AAAA, "::1").unwrap();
+        assert!(builder().delete_record_value(&value).is_err());
+    }
+
+    #[test]
+    fn lenient_parsing_recovers_from_broken_entries() {
+        let zone = format!("{}{}", ZONE, "bad IN TXT \"unterminated
+after IN A 10.0.0.7
+broken IN A 10.0.0.8 )
+multi IN TXT ( \"a\"
+    \"b\" )
+open IN TXT ( \"c\"
+last IN A 10.0.0.9
+");
+        let (manifest, diagnostics) = Manifest::parse(&zone, ParseMode::Lenient).unwrap();
+        let names: Vec<&str> = manifest.get_records().iter().map(|r| r.get_name().as_str()).collect();
+        assert_eq!(names, ["web", "api", "alias", "after", "multi", "last"]);
+        let errors: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors[0].contains("unterminated quoted string"));
+        assert!(errors[1].contains("unbalanced parenthesis"));
+        assert!(errors[2].contains("unbalanced parenthesis, reached end of file"));
+
+        assert!(Manifest::parse(&zone, ParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn names_are_resolved_against_the_current_origin() {
+        let zone = format!("{}{}", ZONE, "$ORIGIN sub.example.com.
+www IN CNAME web
+@ IN MX 10 mail
+_sip._tcp IN SRV 10 5 5060 sip
+ns IN NS ns1.example.com.
+");
+        let (manifest, _) = Manifest::parse(&zone, ParseMode::Strict).unwrap();
+        let records: Vec<String> = manifest.get_records()[3..].iter().map(|r| r.to_string()).collect();
+        assert_eq!(records, [
+            "www.sub.example.com. IN CNAME web.sub.example.com.",
+            "sub.example.com. IN MX 10 mail.sub.example.com.",
+            "_sip._tcp.sub.example.com. IN SRV 10 5 5060 sip.sub.example.com.",
+            "ns.sub.example.com. IN NS ns1.example.com.",
+        ]);
+        // names relative to the origin of the manifest are kept as written.
+        assert_eq!(manifest.get_records()[2].to_string(), "alias IN CNAME web");
+    }
+
+    #[test]
+    fn cnames_cannot_coexist_with_other_data() {
+        let e = builder().add_record(record("WEB.example.com. IN CNAME api")).err();
+        assert!(e.is_some_and(|e| e.contains("CNAME `WEB.example.com.` conflicts with existing A record")));
+        let e = builder().add_record(record("alias IN TXT \"x\"")).err();
+        assert!(e.is_some_and(|e| e.contains("TXT `alias` conflicts with existing CNAME record")));
+        let e = builder().add_record(record("@ IN CNAME web")).err();
+        assert!(e.is_some_and(|e| e.contains("conflicts with the SOA record of the zone")));
+        assert!(builder().add_record(record("new IN CNAME web")).is_ok());
+
+        // a CNAME RRset holds a single record: adding one replaces it.
+        let b = &mut builder();
+        b.add_record(record("alias 60 IN CNAME api")).unwrap();
+        let rrset: Vec<String> = b.get_rrset(&DomainName::parse("alias").unwrap(), record::CNAME)
+            .iter()
+            .map(|r| r.to_string())
+            .collect();
+        assert_eq!(rrset, vec!["alias 60 IN CNAME api"]);
+    }
+
+    #[test]
+    fn records_are_added_to_their_rrset() {
+        let b = &mut builder();
+        b.add_record(record("web IN A 10.0.0.9")).unwrap();
+        // the same value replaces the record, e.g. to change its TTL.
+        b.add_record(record("web.example.com. 60 IN A 10.0.0.5")).unwrap();
+        let records: Vec<String> = b.build().get_records().iter().map(|r| r.to_string()).collect();
+        assert_eq!(records, [
+            "web.example.com. 60 IN A 10.0.0.5",
+            "web IN A 10.0.0.9",
+            "api IN A 10.0.0.6",
+            "alias IN CNAME web",
+        ]);
+    }
+
+    #[test]
+    fn ttls_are_kept_per_record() {
+        let zone = format!("{}{}", ZONE, "short 60 IN A 10.0.0.7
+class IN 1h A 10.0.0.8
+$TTL 300
+late IN A 10.0.0.9
+");
+        let (manifest, _) = Manifest::parse(&zone, ParseMode::Strict).unwrap();
+        let ttls: Vec<Option<u32>> = manifest.get_records().iter().map(|r| r.get_ttl()).collect();
+        assert_eq!(ttls, [None, None, None, Some(60), Some(3600), Some(300)]);
+        assert_eq!(manifest.get_ttl().get_ttl(), 3600);
+        let records: Vec<String> = manifest.get_records()[3..].iter().map(|r| r.to_string()).collect();
+        assert_eq!(records, ["short 60 IN A 10.0.0.7", "class 3600 IN A 10.0.0.8", "late 300 IN A 10.0.0.9"]);
+    }
+}
@@ -0,0 +1,246 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use rocket::serde::{Deserialize, Serialize};
+use crate::dns_manifest_parser::error::{ParseError, ParseErrorKind};
+use crate::dns_manifest_parser::record::OriginRecord;
+
+// Maximum length in bytes of a label, and of a name in wire format (RFC 1035 §2.3.4).
+const MAX_LABEL: usize = 63;
+const MAX_NAME: usize = 255;
+
+// ------------------------------------------ DomainName -------------------------------------------
+
+// A domain name as written in the zonefile: absolute (`web.example.com.`), relative to the origin
+// of the zone (`web`) or the origin itself (`@`).
+// Names compare case-insensitively (RFC 4343); use `matches` to compare a relative name with an
+// absolute one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", try_from = "String", into = "String")]
+pub(crate) struct DomainName {
+    name: String
+}
+
+impl DomainName {
+    /// parses a domain name, validating the length of its labels and their characters: letters,
+    /// digits, `-` and `_`, or any character escaped with `\`. The leftmost label may be `*`.
+    pub(crate) fn parse(s: &str) -> Result<Self, ParseError> {
+        if s == "@" || s == "." {
+            return Ok(Self{ name: s.to_string() })
+        }
+
+        let labels = split_labels(s)?;
+        // each label is preceded by its length, and the name ends with the root label.
+        let length: usize = labels.iter().map(|l| l.len() + 1).sum::<usize>() + 1;
+        if length > MAX_NAME {
+            return Err(invalid_name(s, &format!(
+                "name should be at most {} bytes long, received: {}", MAX_NAME, length
+            )))
+        }
+
+        for (i, label) in labels.iter().enumerate() {
+            if label.is_empty() {
+                return Err(invalid_name(s, "name should not contain empty labels"))
+            }
+            if label.len() > MAX_LABEL {
+                return Err(invalid_name(s, &format!(
+                    "label should be at most {} bytes long, received: {}", MAX_LABEL, label.len()
+                )))
+            }
+            if i == 0 && label == "*" {
+                continue
+            }
+            if let Some(c) = label.chars().find(|&c| !(c.is_ascii_alphanumeric() || c == '-' || c == '_')) {
+                return Err(invalid_name(s, &format!("label should not contain `{}`", c)))
+            }
+        }
+        Ok(Self{ name: s.to_string() })
+    }
+
+    /// builds a name computed by betterdns itself, e.g. the name of a reverse zone.
+    pub(crate) fn new_unchecked(name: &str) -> Self {
+        Self{ name: name.to_string() }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn is_absolute(&self) -> bool {
+        self.name.ends_with('.')
+    }
+
+    /// tells whether the name is a wildcard, e.g. `*` or `*.dev.example.com.` (RFC 4592).
+    pub(crate) fn is_wildcard(&self) -> bool {
+        self.name == "*" || self.name.starts_with("*.")
+    }
+
+    /// resolves a relative name against the origin of the zone.
+    pub(crate) fn resolve(&self, origin: &OriginRecord) -> Self {
+        Self{ name: fqdn(&self.name, &origin.get_origin()) }
+    }
+
+    /// returns the absolute, lowercase form of the name, i.e. a key identifying its node.
+    pub(crate) fn to_canonical(&self, origin: &OriginRecord) -> String {
+        self.resolve(origin).name.to_ascii_lowercase()
+    }
+
+    /// tells whether the name is the origin or one of its subdomains.
+    pub(crate) fn is_in_zone(&self, origin: &OriginRecord) -> bool {
+        let (name, origin) = (self.to_canonical(origin), origin.get_origin().to_ascii_lowercase());
+        name == origin || name.ends_with(&format!(".{}", origin))
+    }
+
+    /// tells whether both names designate the same node of the zone of `origin`.
+    pub(crate) fn matches(&self, other: &DomainName, origin: &OriginRecord) -> bool {
+        self.resolve(origin) == other.resolve(origin)
+    }
+}
+
+impl PartialEq for DomainName {
+    fn eq(&self, other: &Self) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+
+impl Display for DomainName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl TryFrom<String> for DomainName {
+    type Error = ParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<DomainName> for String {
+    fn from(name: DomainName) -> Self {
+        name.name
+    }
+}
+
+/// fqdn resolves a possibly relative name against `origin`.
+pub(crate) fn fqdn(name: &str, origin: &str) -> String {
+    if name == "@" {
+        return origin.to_string()
+    }
+    if name.ends_with('.') {
+        return name.to_string()
+    }
+    format!("{}.{}", name, origin)
+}
+
+// split_labels splits a name on unescaped dots. Escaped characters are replaced by `_`, so that
+// they pass the validation of label characters while keeping the length of the label.
+fn split_labels(s: &str) -> Result<Vec<String>, ParseError> {
+    let mut labels: Vec<String> = vec![];
+    let mut label = String::new();
+    let mut absolute = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        absolute = c == '.';
+        match c {
+            '.' => labels.push(std::mem::take(&mut label)),
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() => {
+                    let digits: String = [Some(d), chars.next(), chars.next()].iter().flatten().collect();
+                    if digits.len() != 3 || digits.parse::<u8>().is_err() {
+                        return Err(invalid_name(s, "invalid `\\DDD` escape"))
+                    }
+                    label.push('_')
+                }
+                Some(_) => label.push('_'),
+                None => return Err(invalid_name(s, "dangling escape")),
+            },
+            c => label.push(c),
+        }
+    }
+    // the root label of an absolute name is implicit.
+    if !absolute {
+        labels.push(label)
+    }
+    Ok(labels)
+}
+
+fn invalid_name(s: &str, message: &str) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidValue, s, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::serde::json;
+    use super::DomainName;
+    use crate::dns_manifest_parser::record::OriginRecord;
+
+    fn parse(s: &str) -> Result<DomainName, String> {
+        DomainName::parse(s).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn labels_and_names_are_limited_in_length() {
+        let label = |n: usize| "a".repeat(n);
+        assert!(parse(&label(63)).is_ok());
+        assert!(parse(&label(64)).is_err_and(|e| e.contains("label should be at most 63 bytes long, received: 64")));
+        // escaped characters count as one byte.
+        assert!(parse(&format!("{}\\.", label(62))).is_ok());
+        assert!(parse(&format!("{}\\046", label(63))).is_err());
+
+        let name = |last: usize| format!("{}.{}.{}.{}.", label(63), label(63), label(63), label(last));
+        assert!(parse(&name(61)).is_ok());
+        assert!(parse(&name(62)).is_err_and(|e| e.contains("name should be at most 255 bytes long, received: 256")));
+    }
+
+    #[test]
+    fn labels_hold_letters_digits_hyphens_and_underscores() {
+        for name in ["web", "Web-01", "_sip._tcp", "xn--bcher-kva.example.", "a\\ b", "a\\065b", "@", "."] {
+            assert!(parse(name).is_ok(), "{} should parse", name);
+        }
+        for (name, message) in [
+            ("we b", "label should not contain ` `"),
+            ("web!", "label should not contain `!`"),
+            ("café", "label should not contain `é`"),
+            ("a..b", "name should not contain empty labels"),
+            (".web", "name should not contain empty labels"),
+            ("a\\300", "invalid `\\DDD` escape"),
+            ("a\\06", "invalid `\\DDD` escape"),
+            ("a\\", "dangling escape"),
+        ] {
+            assert!(parse(name).is_err_and(|e| e.contains(message)), "{} should fail with: {}", name, message);
+        }
+        assert!(json::from_str::<DomainName>("\"we b\"").is_err());
+        assert!(json::from_str::<DomainName>("\"web\"").is_ok());
+    }
+
+    #[test]
+    fn wildcards_are_leftmost_labels() {
+        for name in ["*", "*.dev", "*.dev.example.com."] {
+            assert!(parse(name).is_ok_and(|n| n.is_wildcard()), "{} should be a wildcard", name);
+        }
+        for name in ["a.*.dev", "*a.dev", "a*"] {
+            assert!(parse(name).is_err(), "{} should not parse", name);
+        }
+        assert!(!parse("\\*.dev").unwrap().is_wildcard());
+    }
+
+    #[test]
+    fn names_compare_case_insensitively_and_resolve_against_the_origin() {
+        let origin = &OriginRecord::from_name("example.com.");
+        let (web, upper, absolute) = (parse("web").unwrap(), parse("Web").unwrap(), parse("WEB.example.com.").unwrap());
+        assert_eq!(web, upper);
+        assert_ne!(web, absolute);
+        assert!(web.matches(&absolute, origin));
+        assert!(upper.matches(&absolute, origin));
+        assert_eq!(absolute.to_canonical(origin), "web.example.com.");
+        assert!(!web.matches(&parse("web.example.org.").unwrap(), origin));
+
+        let apex = parse("@").unwrap();
+        assert_eq!(apex.resolve(origin).as_str(), "example.com.");
+        assert!(apex.matches(&parse("Example.COM.").unwrap(), origin));
+        assert!(apex.is_in_zone(origin));
+        assert!(web.is_in_zone(origin));
+        assert!(!parse("badexample.com.").unwrap().is_in_zone(origin));
+    }
+}
@@ -1,103 +1,447 @@
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use regex::Regex;
-use rocket::serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::time::SystemTime;
+use rocket::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use rocket::serde::de::DeserializeOwned;
 use rocket::serde::json::Json;
+use crate::dns_manifest_parser::encoding::{from_base32hex, from_base64, from_hex, to_base64};
+use crate::dns_manifest_parser::error::{ParseError, ParseErrorKind};
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::serial;
+use crate::dns_manifest_parser::serial::SerialPolicy;
+use crate::dns_manifest_parser::tokenizer::{tokenize, Token};
 
-const TTL: &str = "$TTL";
-const ORIGIN: &str = "$ORIGIN";
+pub(crate) mod wire;
+
+pub(crate) const TTL: &str = "$TTL";
+pub(crate) const ORIGIN: &str = "$ORIGIN";
 
 // RecordType
 pub(crate) const A: &str = "A";
+pub(crate) const AAAA: &str = "AAAA";
+pub(crate) const CNAME: &str = "CNAME";
+pub(crate) const MX: &str = "MX";
 pub(crate) const NS: &str = "NS";
+pub(crate) const PTR: &str = "PTR";
 pub(crate) const SOA: &str = "SOA";
+pub(crate) const SRV: &str = "SRV";
+pub(crate) const TXT: &str = "TXT";
+pub(crate) const DNSKEY: &str = "DNSKEY";
+pub(crate) const DS: &str = "DS";
+pub(crate) const RRSIG: &str = "RRSIG";
+pub(crate) const NSEC: &str = "NSEC";
+pub(crate) const NSEC3: &str = "NSEC3";
+pub(crate) const NSEC3PARAM: &str = "NSEC3PARAM";
+
+// Types held in their own format rather than as `UnknownRecord`.
+const MODELED_TYPES: [&str; 15] = [
+    A, AAAA, CNAME, MX, NS, PTR, SOA, SRV, TXT, DNSKEY, DS, RRSIG, NSEC, NSEC3, NSEC3PARAM,
+];
+
+// Marker of rdata written in the generic form `\# <length> <hex>` (RFC 3597 §5).
+const GENERIC_RDATA: &str = "\\#";
+
+// TYPE values of the record types, including types betterdns does not hold but which may appear
+// in the type list of NSEC records (RFC 1035 §3.2.2, RFC 3596, RFC 2782, RFC 4034, RFC 5155).
+const TYPE_CODES: [(&str, u16); 25] = [
+    (A, 1), (NS, 2), (CNAME, 5), (SOA, 6), (PTR, 12), ("HINFO", 13), (MX, 15), (TXT, 16),
+    ("RP", 17), ("AFSDB", 18), (AAAA, 28), ("LOC", 29), (SRV, 33), ("NAPTR", 35), (DS, 43),
+    ("SSHFP", 44), (RRSIG, 46), (NSEC, 47), (DNSKEY, 48), (NSEC3, 50), (NSEC3PARAM, 51),
+    ("TLSA", 52), ("SVCB", 64), ("HTTPS", 65), ("CAA", 257),
+];
+
+// DNSSEC algorithm mnemonics (RFC 8624 §3.1).
+const ALGORITHMS: [(&str, u8); 8] = [
+    ("RSASHA1", 5), ("RSASHA1-NSEC3-SHA1", 7), ("RSASHA256", 8), ("RSASHA512", 10),
+    ("ECDSAP256SHA256", 13), ("ECDSAP384SHA384", 14), ("ED25519", 15), ("ED448", 16),
+];
+
+// Lengths in bytes of the digests of DS records, by digest type (RFC 4509, RFC 6605).
+const DIGEST_LENGTHS: [(u8, usize); 3] = [(1, 20), (2, 32), (4, 48)];
+
+const DNSKEY_PROTOCOL: u8 = 3;
+
+// Maximum length in bytes of a single <character-string> (RFC 1035 §3.3).
+const MAX_CHARACTER_STRING: usize = 255;
 
 // SOA default values
-const DEFAULT_TTL: u32 = 3600;
-const DEFAULT_REFRESH: u32 = 7200;
-const DEFAULT_RETRY: u32 = 3600;
-const DEFAULT_EXPIRE: u32 = 604800;
-const DEFAULT_MINIMUM: u32 = 300;
+pub(crate) const DEFAULT_TTL: u32 = 3600;
 
 // ------------------------------------------ RecordData -------------------------------------------
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
+#[allow(clippy::upper_case_acronyms)]
 pub(crate) enum RecordData {
-    A(Record),
-    NS(Record),
+    A(Record<ARdata>),
+    AAAA(Record<AAAARdata>),
+    CNAME(Record<CNAMERdata>),
+    MX(MXRecord),
+    NS(Record<NSRdata>),
+    PTR(Record<PTRRdata>),
     SOA(SOARecord),
+    SRV(SRVRecord),
+    TXT(TXTRecord),
+    DNSKEY(DNSKEYRecord),
+    DS(DSRecord),
+    RRSIG(RRSIGRecord),
+    NSEC(NSECRecord),
+    NSEC3(NSEC3Record),
+    NSEC3PARAM(NSEC3PARAMRecord),
+    // records of any other type, kept in the generic form of RFC 3597.
+    Unknown(UnknownRecord),
 }
 
 impl RecordData {
-    pub(crate) fn get_name(&self) -> String {
+    pub(crate) fn get_name(&self) -> &DomainName {
         match &self {
-            Self::A(r) => r.name.to_string(),
-            Self::NS(r) => r.name.to_string(),
-            Self::SOA(r) => r.name.to_string(),
+            Self::A(r) => &r.name,
+            Self::AAAA(r) => &r.name,
+            Self::CNAME(r) => &r.name,
+            Self::MX(r) => &r.name,
+            Self::NS(r) => &r.name,
+            Self::PTR(r) => &r.name,
+            Self::SOA(r) => &r.name,
+            Self::SRV(r) => &r.name,
+            Self::TXT(r) => &r.name,
+            Self::DNSKEY(r) => &r.name,
+            Self::DS(r) => &r.name,
+            Self::RRSIG(r) => &r.name,
+            Self::NSEC(r) => &r.name,
+            Self::NSEC3(r) => &r.name,
+            Self::NSEC3PARAM(r) => &r.name,
+            Self::Unknown(r) => &r.name,
         }
     }
 
     pub(crate) fn get_type(&self) -> &str {
         match &self {
             Self::A(_) => A,
+            Self::AAAA(_) => AAAA,
+            Self::CNAME(_) => CNAME,
+            Self::MX(_) => MX,
             Self::NS(_) => NS,
-            Self::SOA(_) => SOA
+            Self::PTR(_) => PTR,
+            Self::SOA(_) => SOA,
+            Self::SRV(_) => SRV,
+            Self::TXT(_) => TXT,
+            Self::DNSKEY(_) => DNSKEY,
+            Self::DS(_) => DS,
+            Self::RRSIG(_) => RRSIG,
+            Self::NSEC(_) => NSEC,
+            Self::NSEC3(_) => NSEC3,
+            Self::NSEC3PARAM(_) => NSEC3PARAM,
+            Self::Unknown(r) => &r.record_type,
         }
     }
 
-    pub(crate) fn from_str(s: &str) -> Result<Self, String> {
-        let v = split_str(s);
-        let length = v.len();
+    /// returns the TTL of the record, when it differs from the `$TTL` of the zone.
+    pub(crate) fn get_ttl(&self) -> Option<u32> {
+        match &self {
+            Self::A(r) => r.ttl,
+            Self::AAAA(r) => r.ttl,
+            Self::CNAME(r) => r.ttl,
+            Self::MX(r) => r.ttl,
+            Self::NS(r) => r.ttl,
+            Self::PTR(r) => r.ttl,
+            Self::SOA(r) => r.ttl,
+            Self::SRV(r) => r.ttl,
+            Self::TXT(r) => r.ttl,
+            Self::DNSKEY(r) => r.ttl,
+            Self::DS(r) => r.ttl,
+            Self::RRSIG(r) => r.ttl,
+            Self::NSEC(r) => r.ttl,
+            Self::NSEC3(r) => r.ttl,
+            Self::NSEC3PARAM(r) => r.ttl,
+            Self::Unknown(r) => r.ttl,
+        }
+    }
+
+    /// returns the domain name the record points to, if any, e.g. the exchange of a MX record.
+    pub(crate) fn get_target(&self) -> Option<&DomainName> {
+        match &self {
+            Self::CNAME(r) => Some(&r.value),
+            Self::NS(r) => Some(&r.value),
+            Self::PTR(r) => Some(&r.value),
+            Self::MX(r) => Some(&r.exchange),
+            // `.` means the service is decidedly not available.
+            Self::SRV(r) if r.target.as_str() != "." => Some(&r.target),
+            _ => None,
+        }
+    }
 
-        if length == 1 && v[0].is_empty() {
-            return Err("skipping empty line".to_string())
+    /// returns a copy of the record whose owner and target names are absolute, e.g. to be written
+    /// out of the context of its zone.
+    pub(crate) fn resolve(&self, origin: &OriginRecord) -> Self {
+        let mut record = self.clone();
+        for name in record.names_mut() {
+            *name = name.resolve(origin)
+        }
+        record
+    }
+
+    fn names_mut(&mut self) -> Vec<&mut DomainName> {
+        match self {
+            Self::A(r) => vec![&mut r.name],
+            Self::AAAA(r) => vec![&mut r.name],
+            Self::CNAME(r) => vec![&mut r.name, &mut r.value],
+            Self::MX(r) => vec![&mut r.name, &mut r.exchange],
+            Self::NS(r) => vec![&mut r.name, &mut r.value],
+            Self::PTR(r) => vec![&mut r.name, &mut r.value],
+            Self::SOA(r) => vec![&mut r.name, &mut r.mname, &mut r.rname],
+            Self::SRV(r) => vec![&mut r.name, &mut r.target],
+            Self::TXT(r) => vec![&mut r.name],
+            Self::DNSKEY(r) => vec![&mut r.name],
+            Self::DS(r) => vec![&mut r.name],
+            Self::RRSIG(r) => vec![&mut r.name, &mut r.signer_name],
+            Self::NSEC(r) => vec![&mut r.name, &mut r.next_name],
+            Self::NSEC3(r) => vec![&mut r.name],
+            Self::NSEC3PARAM(r) => vec![&mut r.name],
+            Self::Unknown(r) => vec![&mut r.name],
+        }
+    }
+
+    /// tells whether both records hold the same data, comparing their rdata in canonical form,
+    /// e.g. `web` and `WEB.example.com.` for the origin `example.com.`.
+    pub(crate) fn has_same_rdata(&self, other: &RecordData, origin: &OriginRecord) -> bool {
+        match (wire::canonical_rdata(self, origin), wire::canonical_rdata(other, origin)) {
+            (Ok(rdata), Ok(other_rdata)) => self.get_type() == other.get_type() && rdata == other_rdata,
+            _ => self.get_type() == other.get_type() && self.get_rdata() == other.get_rdata(),
+        }
+    }
+
+    /// returns the rdata of the record in zone format, i.e. what follows its type.
+    pub(crate) fn get_rdata(&self) -> String {
+        match &self {
+            Self::A(r) => r.value.to_string(),
+            Self::AAAA(r) => r.value.to_string(),
+            Self::CNAME(r) => r.value.to_string(),
+            Self::NS(r) => r.value.to_string(),
+            Self::PTR(r) => r.value.to_string(),
+            Self::MX(r) => r.rdata(),
+            Self::SOA(r) => r.rdata(),
+            Self::SRV(r) => r.rdata(),
+            Self::TXT(r) => r.rdata(),
+            Self::DNSKEY(r) => r.rdata(),
+            Self::DS(r) => r.rdata(),
+            Self::RRSIG(r) => r.rdata(),
+            Self::NSEC(r) => r.rdata(),
+            Self::NSEC3(r) => r.rdata(),
+            Self::NSEC3PARAM(r) => r.rdata(),
+            Self::Unknown(r) => r.rdata(),
+        }
+    }
+
+    /// parses a record of `name` from its rdata in zone format, e.g. `10 relay-1` for MX.
+    pub(crate) fn from_rdata(name: &DomainName, record_type: &str, rdata: &str) -> Result<Self, ParseError> {
+        let (entries, errors) = tokenize(rdata);
+        if let Some(e) = errors.into_iter().next() {
+            return Err(e)
+        }
+        if entries.len() > 1 {
+            return Err(invalid_value(rdata, "rdata should be written on a single line"))
         }
+        let mut tokens = vec![Token::new(name.as_str(), 1, 1), Token::new("IN", 1, 1), Token::new(record_type, 1, 1)];
+        tokens.extend(entries.into_iter().flat_map(|e| e.tokens));
+        Self::from_tokens(&tokens, None)
+    }
+
+    /// parses a record from its tokens: `<name> <class> <type> <rdata>...`.
+    /// `ttl` is the TTL of the record when it differs from the `$TTL` of the zone.
+    pub(crate) fn from_tokens(v: &[Token], ttl: Option<u32>) -> Result<Self, ParseError> {
+        let length = v.len();
 
-        if length != 4 && length != 10 {
-            return Err(format!(
-                "length of DNS record should be equal to 4 or 10, received: {}",
-                length
-            ));
+        if length < 3 {
+            let message = format!("length of DNS record should be at least 3, received: {}", length);
+            return Err(match v.last() {
+                Some(t) => ParseError::new(ParseErrorKind::MissingField, &t.text, message).at_token(t),
+                None => ParseError::new(ParseErrorKind::MissingField, "", message),
+            })
+        }
+        if v.get(3).is_some_and(|t| t.text == GENERIC_RDATA) {
+            return Self::from_generic(v, ttl)
         }
-        let record_type = v[2].as_str();
+        let record_type = v[2].text.to_uppercase();
+        let record_type = record_type.as_str();
         match record_type {
-            A => Ok(RecordData::A(Record {
-                name: v[0].to_string(),
-                class: v[1].to_string(),
-                record_type: record_type.to_string(),
-                value: v[3].to_string(),
-            })),
-            NS => Ok(RecordData::NS(Record {
-                name: v[0].to_string(),
-                class: v[1].to_string(),
-                record_type: record_type.to_string(),
-                value: v[3].to_string(),
+            A | AAAA | CNAME | NS | PTR => expect_length(v, 4)?,
+            MX => expect_length(v, 5)?,
+            SOA => expect_length(v, 10)?,
+            SRV => expect_length(v, 7)?,
+            TXT if length < 4 => return Err(ParseError::new(
+                ParseErrorKind::MissingField,
+                &v[2].text,
+                "TXT record should contain at least one character-string".to_string()
+            ).at_token(&v[2])),
+            DNSKEY | DS => expect_min_length(v, 7)?,
+            RRSIG => expect_min_length(v, 12)?,
+            NSEC => expect_min_length(v, 4)?,
+            NSEC3 => expect_min_length(v, 8)?,
+            NSEC3PARAM => expect_length(v, 7)?,
+            _ => {}
+        }
+        match record_type {
+            A => Ok(RecordData::A(Record::from_tokens(v, ttl)?)),
+            AAAA => Ok(RecordData::AAAA(Record::from_tokens(v, ttl)?)),
+            CNAME => Ok(RecordData::CNAME(Record::from_tokens(v, ttl)?)),
+            MX => Ok(RecordData::MX(MXRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                record_type: RecordType::default(),
+                preference: parse_u16(&v[3], "preference")?,
+                exchange: parse_name(&v[4])?,
             })),
+            NS => Ok(RecordData::NS(Record::from_tokens(v, ttl)?)),
+            PTR => Ok(RecordData::PTR(Record::from_tokens(v, ttl)?)),
             SOA => {
-                if length != 10 {
-                    return Err(format!(
-                        "length of SOA record should be equal to 10, received: {}",
-                        length
-                    ));
-                }
-
                 Ok(RecordData::SOA(SOARecord {
-                    name: v[0].to_string(),
-                    class: v[1].to_string(),
-                    mname: v[3].to_string(),
-                    rname: v[4].to_string(),
-                    serial: v[5].parse().unwrap(), // serial is mandatory.
-                    refresh: v[6].parse().unwrap_or(DEFAULT_REFRESH),
-                    retry: v[7].parse().unwrap_or(DEFAULT_RETRY),
-                    expire: v[8].parse().unwrap_or(DEFAULT_EXPIRE),
-                    minimum: v[9].parse().unwrap_or(DEFAULT_MINIMUM),
+                    name: parse_name(&v[0])?,
+                    ttl,
+                    class: v[1].text.to_string(),
+                    mname: parse_name(&v[3])?,
+                    rname: parse_name(&v[4])?,
+                    serial: parse_u32(&v[5], "serial")?, // serial is mandatory.
+                    refresh: parse_duration(&v[6], "refresh")?,
+                    retry: parse_duration(&v[7], "retry")?,
+                    expire: parse_duration(&v[8], "expire")?,
+                    minimum: parse_duration(&v[9], "minimum")?,
                 }))
             }
-            _ => Err(format!(
-                "record_type should be equal to `A`, `NS` or `SOA`, received: `{}`",
-                record_type
-            )),
+            SRV => Ok(RecordData::SRV(SRVRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                record_type: RecordType::default(),
+                priority: parse_u16(&v[3], "priority")?,
+                weight: parse_u16(&v[4], "weight")?,
+                port: parse_u16(&v[5], "port")?,
+                target: parse_name(&v[6])?,
+            })),
+            TXT => Ok(RecordData::TXT(TXTRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                record_type: RecordType::default(),
+                strings: v[3..]
+                    .iter()
+                    .map(|t| CharacterString::parse(&t.text).map_err(|e| e.at_token(t)))
+                    .collect::<Result<Vec<CharacterString>, ParseError>>()?,
+            })),
+            DNSKEY => RecordData::DNSKEY(DNSKEYRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                flags: parse_u16(&v[3], "flags")?,
+                protocol: parse_u8(&v[4], "protocol")?,
+                algorithm: parse_algorithm(&v[5])?,
+                public_key: join_tokens(&v[6..]),
+            }).validated(&v[6]),
+            DS => RecordData::DS(DSRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                key_tag: parse_u16(&v[3], "key tag")?,
+                algorithm: parse_algorithm(&v[4])?,
+                digest_type: parse_u8(&v[5], "digest type")?,
+                digest: join_tokens(&v[6..]).to_uppercase(),
+            }).validated(&v[6]),
+            RRSIG => RecordData::RRSIG(RRSIGRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                type_covered: v[3].text.to_uppercase(),
+                algorithm: parse_algorithm(&v[4])?,
+                labels: parse_u8(&v[5], "labels")?,
+                original_ttl: parse_u32(&v[6], "original TTL")?,
+                expiration: parse_timestamp(&v[7], "expiration")?,
+                inception: parse_timestamp(&v[8], "inception")?,
+                key_tag: parse_u16(&v[9], "key tag")?,
+                signer_name: parse_name(&v[10])?,
+                signature: join_tokens(&v[11..]),
+            }).validated(&v[3]),
+            NSEC => RecordData::NSEC(NSECRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                next_name: parse_name(&v[3])?,
+                types: parse_types(&v[4..]),
+            }).validated(&v[3]),
+            NSEC3 => RecordData::NSEC3(NSEC3Record {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                hash_algorithm: parse_u8(&v[3], "hash algorithm")?,
+                flags: parse_u8(&v[4], "flags")?,
+                iterations: parse_u16(&v[5], "iterations")?,
+                salt: parse_salt(&v[6]),
+                next_hashed_owner: v[7].text.to_uppercase(),
+                types: parse_types(&v[8..]),
+            }).validated(&v[6]),
+            NSEC3PARAM => RecordData::NSEC3PARAM(NSEC3PARAMRecord {
+                name: parse_name(&v[0])?,
+                ttl,
+                class: v[1].text.to_string(),
+                hash_algorithm: parse_u8(&v[3], "hash algorithm")?,
+                flags: parse_u8(&v[4], "flags")?,
+                iterations: parse_u16(&v[5], "iterations")?,
+                salt: parse_salt(&v[6]),
+            }).validated(&v[6]),
+            _ => Err(ParseError::new(
+                ParseErrorKind::UnknownType,
+                &v[2].text,
+                "record_type should be equal to `A`, `AAAA`, `CNAME`, `MX`, `NS`, `PTR`, `SOA`, `SRV`, `TXT`, \
+                `DNSKEY`, `DS`, `RRSIG`, `NSEC`, `NSEC3` or `NSEC3PARAM`, or its rdata be written in the generic \
+                form `\\# <length> <hex>`".to_string()
+            ).at_token(&v[2])),
+        }
+    }
+
+    // from_generic parses rdata written in the generic form `\# <length> <hex>...`, of any type
+    // (RFC 3597 §5). Rdata of the types betterdns holds is decoded, e.g. `A \# 4 0A000001`.
+    fn from_generic(v: &[Token], ttl: Option<u32>) -> Result<Self, ParseError> {
+        expect_min_length(v, 5)?;
+        let code = type_code(&v[2].text).ok_or_else(|| ParseError::new(
+            ParseErrorKind::UnknownType,
+            &v[2].text,
+            "record type should be a known mnemonic or written `TYPEnnn`".to_string()
+        ).at_token(&v[2]))?;
+        let length = parse_u16(&v[4], "rdata length")?;
+        let rdata = from_hex(&join_tokens(&v[5..])).map_err(|e| invalid_value(&v[4].text, &e).at_token(&v[4]))?;
+        if rdata.len() != usize::from(length) {
+            return Err(invalid_value(&v[4].text, &format!(
+                "rdata should be {} bytes long, received: {}", length, rdata.len()
+            )).at_token(&v[4]))
+        }
+        wire::decode_rdata(parse_name(&v[0])?, ttl, &v[1].text, code, &rdata)
+            .map_err(|e| e.at_token(&v[3]))?
+            .validated(&v[3])
+    }
+
+    // validated locates the validation errors of rdata spanning several tokens at the first one.
+    fn validated(self, t: &Token) -> Result<Self, ParseError> {
+        self.validate().map_err(|e| e.at_token(t))?;
+        Ok(self)
+    }
+
+    /// validates the value of a record that did not go through `from_tokens`, e.g. received as
+    /// json, beyond what its type guarantees.
+    pub(crate) fn validate(&self) -> Result<(), ParseError> {
+        match self {
+            Self::MX(r) => r.validate(),
+            Self::SRV(r) => r.validate(),
+            Self::TXT(r) => r.validate(),
+            Self::DNSKEY(r) => r.validate(),
+            Self::DS(r) => r.validate(),
+            Self::RRSIG(r) => r.validate(),
+            Self::NSEC(r) => r.validate(),
+            Self::NSEC3(r) => r.validate(),
+            Self::NSEC3PARAM(r) => r.validate(),
+            Self::Unknown(r) => r.validate(),
+            _ => Ok(())
         }
     }
 }
@@ -106,23 +450,123 @@ impl Display for RecordData {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Self::A(r) => write!(f, "{}", r),
+            Self::AAAA(r) => write!(f, "{}", r),
+            Self::CNAME(r) => write!(f, "{}", r),
+            Self::MX(r) => write!(f, "{}", r),
             Self::NS(r) => write!(f, "{}", r),
+            Self::PTR(r) => write!(f, "{}", r),
             Self::SOA(r) => write!(f, "{}", r),
+            Self::SRV(r) => write!(f, "{}", r),
+            Self::TXT(r) => write!(f, "{}", r),
+            Self::DNSKEY(r) => write!(f, "{}", r),
+            Self::DS(r) => write!(f, "{}", r),
+            Self::RRSIG(r) => write!(f, "{}", r),
+            Self::NSEC(r) => write!(f, "{}", r),
+            Self::NSEC3(r) => write!(f, "{}", r),
+            Self::NSEC3PARAM(r) => write!(f, "{}", r),
+            Self::Unknown(r) => write!(f, "{}", r),
         }
     }
 }
 
-fn split_str(s: &str) -> Vec<String> {
-    let s = clean_str(s);
-    s.split(" ").map(|s| s.to_string()).collect()
+// format_owner formats the `<name> [<ttl>] <class>` prefix shared by all records.
+fn format_owner(name: &DomainName, ttl: Option<u32>, class: &str) -> String {
+    match ttl {
+        Some(ttl) => format!("{} {} {}", name, ttl, class),
+        None => format!("{} {}", name, class),
+    }
 }
 
-fn clean_str(s: &str) -> String {
-    let regex = Regex::new(r"(;.*)+").unwrap();
-    let s = regex.replace_all(s, "").to_string();
+fn expect_length(v: &[Token], expected: usize) -> Result<(), ParseError> {
+    let message = format!(
+        "length of {} record should be equal to {}, received: {}",
+        v[2].text, expected, v.len()
+    );
+    match v.len() {
+        n if n < expected => {
+            let t = &v[n - 1];
+            Err(ParseError::new(ParseErrorKind::MissingField, &t.text, message).at_token(t))
+        }
+        n if n > expected => {
+            let t = &v[expected];
+            Err(ParseError::new(ParseErrorKind::UnexpectedField, &t.text, message).at_token(t))
+        }
+        _ => Ok(())
+    }
+}
 
-    let regex = Regex::new(r" {2,}").unwrap();
-    regex.replace_all(&s, " ").to_string()
+fn expect_min_length(v: &[Token], expected: usize) -> Result<(), ParseError> {
+    match v.len() {
+        n if n < expected => {
+            let t = &v[n - 1];
+            Err(ParseError::new(ParseErrorKind::MissingField, &t.text, format!(
+                "length of {} record should be at least {}, received: {}", v[2].text, expected, n
+            )).at_token(t))
+        }
+        _ => Ok(())
+    }
+}
+
+/// returns the TYPE value of a record type, written as a mnemonic or `TYPEnnn` (RFC 3597 §5).
+pub(crate) fn type_code(name: &str) -> Option<u16> {
+    let name = name.to_ascii_uppercase();
+    match TYPE_CODES.iter().find(|(t, _)| *t == name) {
+        Some((_, code)) => Some(*code),
+        None => name.strip_prefix("TYPE").and_then(|n| n.parse().ok()),
+    }
+}
+
+/// returns the mnemonic of a TYPE value, or its generic form `TYPEnnn`.
+pub(crate) fn type_name(code: u16) -> String {
+    match TYPE_CODES.iter().find(|(_, c)| *c == code) {
+        Some((name, _)) => name.to_string(),
+        None => format!("TYPE{}", code),
+    }
+}
+
+fn parse_name(t: &Token) -> Result<DomainName, ParseError> {
+    DomainName::parse(&t.text).map_err(|e| e.at_token(t))
+}
+
+fn parse_u8(t: &Token, field: &str) -> Result<u8, ParseError> {
+    t.text.parse::<u8>().map_err(|_| ParseError::new(
+        ParseErrorKind::InvalidValue,
+        &t.text,
+        format!("{} should be a parseable u8 integer", field)
+    ).at_token(t))
+}
+
+fn parse_u16(t: &Token, field: &str) -> Result<u16, ParseError> {
+    t.text.parse::<u16>().map_err(|_| ParseError::new(
+        ParseErrorKind::InvalidValue,
+        &t.text,
+        format!("{} should be a parseable u16 integer", field)
+    ).at_token(t))
+}
+
+fn parse_u32(t: &Token, field: &str) -> Result<u32, ParseError> {
+    t.text.parse::<u32>().map_err(|_| ParseError::new(
+        ParseErrorKind::InvalidValue,
+        &t.text,
+        format!("{} should be a parseable u32 integer", field)
+    ).at_token(t))
+}
+
+// parse_duration parses a timer of the SOA record like a TTL, e.g. `7200` or `2h`.
+fn parse_duration(t: &Token, field: &str) -> Result<u32, ParseError> {
+    parse_ttl(&t.text).map_err(|_| ParseError::new(
+        ParseErrorKind::InvalidValue,
+        &t.text,
+        format!("{} should be a parseable u32 integer or a duration, e.g. `1h30m`", field)
+    ).at_token(t))
+}
+
+fn invalid_value(s: &str, message: &str) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidValue, s, message.to_string())
+}
+
+fn syntax_error(s: &str, message: &str) -> ParseError {
+    ParseError::new(ParseErrorKind::Syntax, s, message.to_string())
 }
 
 // ------------------------------------------ SOARecord --------------------------------------------
@@ -130,10 +574,13 @@ fn clean_str(s: &str) -> String {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
 pub(crate) struct SOARecord {
-    name: String,
+    name: DomainName,
+    // TTL of the SOA record itself; the `$TTL` of the zone applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
     class: String, // "IN"
-    mname: String, // Primary master name server for this zone
-    rname: String, // Email address of the administrator responsible for this zone.
+    mname: DomainName, // Primary master name server for this zone
+    rname: DomainName, // Email address of the administrator responsible for this zone.
     // Serial number for this zone.
     // If a secondary name server slaved to this one observes an increase in this number,
     // the slave will assume that the zone has been updated and initiate a zone transfer.
@@ -163,10 +610,11 @@ pub(crate) struct SOARecord {
 impl SOARecord {
     pub(crate) fn new() -> Self {
         Self{
-            name: "".to_string(),
+            name: DomainName::new_unchecked("@"),
+            ttl: None,
             class: "".to_string(),
-            mname: "".to_string(),
-            rname: "".to_string(),
+            mname: DomainName::new_unchecked("@"),
+            rname: DomainName::new_unchecked("@"),
             serial: 0,
             refresh: 0,
             retry: 0,
@@ -175,17 +623,59 @@ impl SOARecord {
         }
     }
 
-    pub(crate) fn increment(&mut self) {
-        self.serial += 1
+    pub(crate) fn get_name(&self) -> &DomainName {
+        &self.name
+    }
+
+    /// returns a copy of this SOA record owned by another zone, e.g. a derived reverse zone.
+    /// `origin` is the origin of the zone this record was read from.
+    pub(crate) fn for_zone(&self, name: &DomainName, origin: &OriginRecord) -> Self {
+        Self{
+            name: name.clone(),
+            mname: self.mname.resolve(origin),
+            rname: self.rname.resolve(origin),
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn get_serial(&self) -> u32 {
+        self.serial
+    }
+
+    pub(crate) fn get_refresh(&self) -> u32 {
+        self.refresh
+    }
+
+    pub(crate) fn get_retry(&self) -> u32 {
+        self.retry
+    }
+
+    pub(crate) fn get_expire(&self) -> u32 {
+        self.expire
+    }
+
+    pub(crate) fn get_minimum(&self) -> u32 {
+        self.minimum
+    }
+
+    pub(crate) fn get_class(&self) -> String {
+        self.class.to_string()
+    }
+
+    /// returns a copy of this SOA record with another serial.
+    pub(crate) fn with_serial(&self, serial: u32) -> Self {
+        Self{ serial, ..self.clone() }
+    }
+
+    /// moves the serial forward according to `policy`.
+    pub(crate) fn increment(&mut self, policy: SerialPolicy) {
+        self.serial = policy.next(self.serial, SystemTime::now())
     }
 }
 
-impl Display for SOARecord {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {} {} {} {} {} {} {} {} {}",
-            self.name,
-            self.class,
-            SOA,
+impl SOARecord {
+    fn rdata(&self) -> String {
+        format!("{} {} {} {} {} {} {}",
             self.mname,
             self.rname,
             self.serial,
@@ -197,39 +687,946 @@ impl Display for SOARecord {
     }
 }
 
+impl Display for SOARecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), SOA, self.rdata())
+    }
+}
+
+// -------------------------------------------- Rdata ----------------------------------------------
+
+/// Typed binds a type to the mnemonic of the records it describes, e.g. `MX`.
+pub(crate) trait Typed {
+    const TYPE: &'static str;
+}
+
+/// Rdata binds a record type to the type of its value: records of another type, or with a value
+/// that does not parse, are rejected when they are deserialized.
+pub(crate) trait Rdata: Typed {
+    type Value: Clone + Debug + Display + Serialize + DeserializeOwned;
+
+    /// parses the value of a record from zone format.
+    fn parse(s: &str) -> Result<Self::Value, ParseError>;
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum ARdata {}
+
+impl Typed for ARdata {
+    const TYPE: &'static str = A;
+}
+
+impl Rdata for ARdata {
+    type Value = Ipv4Addr;
+
+    fn parse(s: &str) -> Result<Ipv4Addr, ParseError> {
+        s.parse().map_err(|_| invalid_value(s, "value of A record should be an IPv4 address"))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum AAAARdata {}
+
+impl Typed for AAAARdata {
+    const TYPE: &'static str = AAAA;
+}
+
+impl Rdata for AAAARdata {
+    type Value = Ipv6Addr;
+
+    fn parse(s: &str) -> Result<Ipv6Addr, ParseError> {
+        s.parse().map_err(|_| invalid_value(s, "value of AAAA record should be an IPv6 address"))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum CNAMERdata {}
+
+impl Typed for CNAMERdata {
+    const TYPE: &'static str = CNAME;
+}
+
+impl Rdata for CNAMERdata {
+    type Value = DomainName;
+
+    fn parse(s: &str) -> Result<DomainName, ParseError> {
+        DomainName::parse(s)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum NSRdata {}
+
+impl Typed for NSRdata {
+    const TYPE: &'static str = NS;
+}
+
+impl Rdata for NSRdata {
+    type Value = DomainName;
+
+    fn parse(s: &str) -> Result<DomainName, ParseError> {
+        DomainName::parse(s)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum PTRRdata {}
+
+impl Typed for PTRRdata {
+    const TYPE: &'static str = PTR;
+}
+
+impl Rdata for PTRRdata {
+    type Value = DomainName;
+
+    fn parse(s: &str) -> Result<DomainName, ParseError> {
+        DomainName::parse(s)
+    }
+}
+
+// RecordType is the `record_type` of a record, i.e. always `T::TYPE`, e.g. of a `Record<T>` or
+// a `MXRecord`. It may be omitted in json, and any other type is rejected.
+#[derive(Clone, Debug)]
+pub(crate) struct RecordType<T: Typed>(PhantomData<T>);
+
+impl<T: Typed> Default for RecordType<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Typed> Serialize for RecordType<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(T::TYPE)
+    }
+}
+
+impl<'de, T: Typed> Deserialize<'de> for RecordType<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        match s.eq_ignore_ascii_case(T::TYPE) {
+            true => Ok(Self::default()),
+            false => Err(de::Error::custom(format!(
+                "record_type should be equal to `{}`, received: `{}`", T::TYPE, s
+            )))
+        }
+    }
+}
+
 // -------------------------------------------- Record ---------------------------------------------
 
+// Record holds the records made of a single value: an address or a domain name.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", bound = "")]
+pub(crate) struct Record<T: Rdata> {
+    name: DomainName,
+    // TTL of this record, the `$TTL` of the zone applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    #[serde(default)]
+    record_type: RecordType<T>,
+    value: T::Value,
+}
+
+impl<T: Rdata> Record<T> {
+    pub(crate) fn new(name: DomainName, ttl: Option<u32>, class: &str, value: T::Value) -> Self {
+        Self{
+            name,
+            ttl,
+            class: class.to_string(),
+            record_type: RecordType::default(),
+            value,
+        }
+    }
+
+    pub(crate) fn get_ttl(&self) -> Option<u32> {
+        self.ttl
+    }
+
+    pub(crate) fn get_class(&self) -> String {
+        self.class.to_string()
+    }
+
+    pub(crate) fn get_value(&self) -> &T::Value {
+        &self.value
+    }
+
+    pub(crate) fn from_json(j: Json<Self>) -> Self {
+        j.into_inner()
+    }
+
+    // from_tokens parses `<name> <class> <type> <value>`, whose length is already checked.
+    fn from_tokens(v: &[Token], ttl: Option<u32>) -> Result<Self, ParseError> {
+        Ok(Self::new(
+            parse_name(&v[0])?,
+            ttl,
+            &v[1].text,
+            T::parse(&v[3].text).map_err(|e| e.at_token(&v[3]))?,
+        ))
+    }
+}
+
+impl<T: Rdata> Display for Record<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}",
+            format_owner(&self.name, self.ttl, &self.class),
+            T::TYPE,
+            self.value
+        )
+    }
+}
+
+// ------------------------------------------- MXRecord --------------------------------------------
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(crate = "rocket::serde")]
-pub(crate) struct Record {
-    name: String,
+pub(crate) struct MXRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
     class: String,
-    record_type: String,
-    value: String,
+    #[serde(default)]
+    record_type: RecordType<Self>,
+    // Lower values are preferred by mail transfer agents.
+    preference: u16,
+    // Host name of the mail server; must not point to a CNAME (RFC 2181 §10.3).
+    exchange: DomainName,
+}
+
+impl Typed for MXRecord {
+    const TYPE: &'static str = MX;
 }
 
-impl Record {
+impl MXRecord {
     pub(crate) fn from_json(j: Json<Self>) -> Self {
+        j.into_inner()
+    }
+}
+
+impl MXRecord {
+    fn validate(&self) -> Result<(), ParseError> {
+        match self.exchange.is_wildcard() {
+            true => Err(invalid_value(self.exchange.as_str(), "exchange should be a host name, not a wildcard")),
+            false => Ok(())
+        }
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {}", self.preference, self.exchange)
+    }
+}
+
+impl Display for MXRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), MX, self.rdata())
+    }
+}
+
+// ------------------------------------------- SRVRecord -------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct SRVRecord {
+    // Owner name of the form `_service._proto.name`, e.g. `_http._tcp.web`.
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    #[serde(default)]
+    record_type: RecordType<Self>,
+    // Clients must contact the target with the lowest priority they can reach.
+    priority: u16,
+    // Relative weight for records with the same priority; higher weights are picked more often.
+    weight: u16,
+    // TCP or UDP port on which the service is to be found.
+    port: u16,
+    // Host name providing the service; `.` means the service is decidedly not available.
+    target: DomainName,
+}
+
+impl Typed for SRVRecord {
+    const TYPE: &'static str = SRV;
+}
+
+impl SRVRecord {
+    pub(crate) fn from_json(j: Json<Self>) -> Self {
+        j.into_inner()
+    }
+}
+
+impl SRVRecord {
+    fn validate(&self) -> Result<(), ParseError> {
+        // the service and protocol labels start with `_` (RFC 2782).
+        let mut labels = self.name.as_str().split('.').map(|l| l.starts_with('_'));
+        if labels.next() != Some(true) || labels.next() != Some(true) {
+            return Err(invalid_value(self.name.as_str(), &format!(
+                "name should be of the form `_service._proto.name`, e.g. `_http._tcp.web`, received: {}", self.name
+            )))
+        }
+        match self.target.is_wildcard() {
+            true => Err(invalid_value(self.target.as_str(), "target should be a host name, not a wildcard")),
+            false => Ok(())
+        }
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {} {} {}", self.priority, self.weight, self.port, self.target)
+    }
+}
+
+impl Display for SRVRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), SRV, self.rdata())
+    }
+}
+
+// ------------------------------------------- TXTRecord -------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct TXTRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    #[serde(default)]
+    record_type: RecordType<Self>,
+    // <character-string>s of the record, each at most 255 bytes long.
+    strings: Vec<CharacterString>,
+}
+
+impl Typed for TXTRecord {
+    const TYPE: &'static str = TXT;
+}
+
+impl TXTRecord {
+    /// builds a TXTRecord from json, splitting strings longer than 255 bytes into several
+    /// character-strings.
+    pub(crate) fn from_json(j: Json<Self>) -> Self {
+        let record = j.into_inner();
+        Self{ strings: record.strings.iter().flat_map(|s| s.split()).collect(), ..record }
+    }
+
+    fn validate(&self) -> Result<(), ParseError> {
+        if self.strings.is_empty() {
+            return Err(ParseError::new(
+                ParseErrorKind::MissingField,
+                "",
+                "TXT record should contain at least one character-string".to_string()
+            ))
+        }
+        match self.strings.iter().find(|s| s.0.len() > MAX_CHARACTER_STRING) {
+            Some(s) => Err(invalid_value(&s.to_string(), &format!(
+                "character-string should be at most {} bytes long, received: {}",
+                MAX_CHARACTER_STRING, s.0.len()
+            ))),
+            None => Ok(())
+        }
+    }
+}
+
+impl TXTRecord {
+    fn rdata(&self) -> String {
+        self.strings
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Display for TXTRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), TXT, self.rdata())
+    }
+}
+
+// CharacterString is an unescaped <character-string>: bytes, usually but not necessarily UTF-8,
+// e.g. `"a\200b"`. Json holds it as a string when it is UTF-8, as an array of bytes otherwise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct CharacterString(Vec<u8>);
+
+impl CharacterString {
+    // parse unescapes a quoted or unquoted <character-string>.
+    // Supports `\X` (literal X) and `\DDD` (decimal byte value) escapes.
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let (inner, quoted) = match s.strip_prefix('"') {
+            Some(rest) => (rest, true),
+            None => (s, false)
+        };
+
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut terminated = false;
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if terminated {
+                return Err(syntax_error(s, "unexpected data after closing quote"))
+            }
+            let c = match c {
+                '"' if quoted => {
+                    terminated = true;
+                    continue
+                }
+                '\\' => match chars.next() {
+                    Some(d) if d.is_ascii_digit() => {
+                        let digits: String = [Some(d), chars.next(), chars.next()]
+                            .iter()
+                            .flatten()
+                            .collect();
+                        match digits.parse::<u8>() {
+                            Ok(b) if digits.len() == 3 => {
+                                bytes.push(b);
+                                continue
+                            }
+                            _ => return Err(syntax_error(s, "invalid `\\DDD` escape"))
+                        }
+                    }
+                    Some(e) => e,
+                    None => return Err(syntax_error(s, "dangling escape"))
+                },
+                c => c
+            };
+            let mut buf = [0; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        }
+
+        if quoted && !terminated {
+            return Err(syntax_error(s, "unterminated character-string"))
+        }
+        if bytes.len() > MAX_CHARACTER_STRING {
+            return Err(invalid_value(s, &format!(
+                "character-string should be at most {} bytes long, received: {}",
+                MAX_CHARACTER_STRING, bytes.len()
+            )))
+        }
+        Ok(Self(bytes))
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    // split splits a string into chunks of at most 255 bytes, on the char boundaries of UTF-8 text.
+    fn split(&self) -> Vec<Self> {
+        let mut chunks: Vec<Self> = Vec::new();
+        let mut rest = self.0.as_slice();
+        while rest.len() > MAX_CHARACTER_STRING {
+            // a chunk does not end before a continuation byte of a char, if any.
+            let end = (MAX_CHARACTER_STRING - 3..=MAX_CHARACTER_STRING)
+                .rev()
+                .find(|&i| rest[i] & 0xC0 != 0x80)
+                .unwrap_or(MAX_CHARACTER_STRING);
+            chunks.push(Self(rest[..end].to_vec()));
+            rest = &rest[end..];
+        }
+        chunks.push(Self(rest.to_vec()));
+        chunks
+    }
+}
+
+impl From<&[u8]> for CharacterString {
+    fn from(bytes: &[u8]) -> Self {
+        Self(bytes.to_vec())
+    }
+}
+
+// Quotes and escapes the string so that it can be parsed back without loss: control characters
+// and bytes which are not UTF-8 are written as `\DDD` escapes.
+impl Display for CharacterString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "\"")?;
+        for chunk in self.0.utf8_chunks() {
+            for c in chunk.valid().chars() {
+                match c {
+                    '"' | '\\' => write!(f, "\\{}", c)?,
+                    c if c.is_control() => {
+                        let mut buf = [0; 4];
+                        for b in c.encode_utf8(&mut buf).bytes() {
+                            write!(f, "\\{:03}", b)?
+                        }
+                    }
+                    c => write!(f, "{}", c)?,
+                }
+            }
+            for b in chunk.invalid() {
+                write!(f, "\\{:03}", b)?
+            }
+        }
+        write!(f, "\"")
+    }
+}
+
+impl Serialize for CharacterString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match std::str::from_utf8(&self.0) {
+            Ok(s) => serializer.serialize_str(s),
+            Err(_) => self.0.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for CharacterString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(crate = "rocket::serde", untagged)]
+        enum Json {
+            Text(String),
+            Bytes(Vec<u8>),
+        }
+        match Json::deserialize(deserializer)? {
+            Json::Text(s) => Ok(Self(s.into_bytes())),
+            Json::Bytes(bytes) => Ok(Self(bytes)),
+        }
+    }
+}
+
+// ----------------------------------------- DNSKEYRecord ------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct DNSKEYRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // 256 for a zone signing key; 257 also sets the Secure Entry Point bit of key signing keys.
+    flags: u16,
+    // always 3 (RFC 4034 §2.1.2).
+    protocol: u8,
+    algorithm: u8,
+    // base64.
+    public_key: String,
+}
+
+impl DNSKEYRecord {
+    pub(crate) fn new(name: DomainName, class: &str, flags: u16, algorithm: u8, public_key: &[u8]) -> Self {
         Self{
-            name: j.name.to_string(),
-            class: j.class.to_string(),
-            record_type: j.record_type.to_string(),
-            value: j.value.to_string(),
+            name,
+            ttl: None,
+            class: class.to_string(),
+            flags,
+            protocol: DNSKEY_PROTOCOL,
+            algorithm,
+            public_key: to_base64(public_key),
+        }
+    }
+
+    /// returns the key tag referring to this key from RRSIG and DS records (RFC 4034 Appendix B).
+    pub(crate) fn key_tag(&self) -> Result<u16, ParseError> {
+        let mut rdata = self.flags.to_be_bytes().to_vec();
+        rdata.extend([self.protocol, self.algorithm]);
+        rdata.extend(parse_base64(&self.public_key, "public key")?);
+        let sum: u32 = rdata
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if i.is_multiple_of(2) { u32::from(b) << 8 } else { u32::from(b) })
+            .sum();
+        Ok((sum + (sum >> 16)) as u16)
+    }
+
+    fn validate(&self) -> Result<(), ParseError> {
+        if self.protocol != DNSKEY_PROTOCOL {
+            return Err(invalid_value(&self.protocol.to_string(), &format!(
+                "protocol should be equal to {}", DNSKEY_PROTOCOL
+            )))
         }
+        parse_base64(&self.public_key, "public key").map(|_| ())
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {} {} {}", self.flags, self.protocol, self.algorithm, self.public_key)
     }
 }
 
-impl Display for Record {
+impl Display for DNSKEYRecord {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {} {} {}",
-            self.name,
-            self.class,
-            self.record_type,
-            self.value
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), DNSKEY, self.rdata())
+    }
+}
+
+// ------------------------------------------- DSRecord --------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct DSRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // Key tag of the DNSKEY record of the child zone this record refers to.
+    key_tag: u16,
+    algorithm: u8,
+    // 1 for SHA-1, 2 for SHA-256, 4 for SHA-384.
+    digest_type: u8,
+    // hex.
+    digest: String,
+}
+
+impl DSRecord {
+    fn validate(&self) -> Result<(), ParseError> {
+        let digest = from_hex(&self.digest).map_err(|e| invalid_value(&self.digest, &e))?;
+        match DIGEST_LENGTHS.iter().find(|(t, _)| *t == self.digest_type) {
+            Some((_, length)) if digest.len() != *length => Err(invalid_value(&self.digest, &format!(
+                "digest of type {} should be {} bytes long, received: {}", self.digest_type, length, digest.len()
+            ))),
+            _ => Ok(())
+        }
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {} {} {}", self.key_tag, self.algorithm, self.digest_type, self.digest)
+    }
+}
+
+impl Display for DSRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), DS, self.rdata())
+    }
+}
+
+// ------------------------------------------ RRSIGRecord ------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct RRSIGRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // Type of the RRset covered by the signature.
+    type_covered: String,
+    algorithm: u8,
+    // Number of labels of the owner name, without the root and a leftmost wildcard.
+    labels: u8,
+    original_ttl: u32,
+    // Validity period of the signature, in seconds since the unix epoch (RFC 4034 §3.1.5).
+    expiration: u32,
+    inception: u32,
+    key_tag: u16,
+    signer_name: DomainName,
+    // base64.
+    signature: String,
+}
+
+impl RRSIGRecord {
+    /// returns the RRSIG record covering `rrset` with `key`, valid from `inception` to
+    /// `expiration`. Its signature is empty until set by `with_signature`.
+    /// `ttl` is the TTL of records without one.
+    pub(crate) fn covering(
+        rrset: &[RecordData],
+        ttl: u32,
+        key: &DNSKEYRecord,
+        (inception, expiration): (u32, u32),
+    ) -> Result<Self, ParseError> {
+        let Some(record) = rrset.first() else {
+            return Err(invalid_value("", "RRset should contain at least one record"))
+        };
+        let labels = wire::canonical_labels(record.get_name())?;
+        let wildcard = labels.first().is_some_and(|l| l.as_slice() == b"*");
+        Ok(Self{
+            name: record.get_name().clone(),
+            ttl: record.get_ttl(),
+            class: key.class.to_string(),
+            type_covered: record.get_type().to_string(),
+            algorithm: key.algorithm,
+            labels: (labels.len() - usize::from(wildcard)) as u8,
+            original_ttl: record.get_ttl().unwrap_or(ttl),
+            expiration,
+            inception,
+            key_tag: key.key_tag()?,
+            signer_name: key.name.clone(),
+            signature: String::new(),
+        })
+    }
+
+    pub(crate) fn with_signature(self, signature: &[u8]) -> Self {
+        Self{ signature: to_base64(signature), ..self }
+    }
+
+    fn validate(&self) -> Result<(), ParseError> {
+        validate_types(std::slice::from_ref(&self.type_covered))?;
+        parse_base64(&self.signature, "signature").map(|_| ())
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {} {} {} {} {} {} {} {}",
+            self.type_covered,
+            self.algorithm,
+            self.labels,
+            self.original_ttl,
+            format_timestamp(self.expiration),
+            format_timestamp(self.inception),
+            self.key_tag,
+            self.signer_name,
+            self.signature,
         )
     }
 }
 
+impl Display for RRSIGRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), RRSIG, self.rdata())
+    }
+}
+
+// ------------------------------------------ NSECRecord -------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct NSECRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // Next owner name of the zone in canonical order; the last NSEC record points to the apex.
+    next_name: DomainName,
+    // Types of the records owned by the name.
+    types: Vec<String>,
+}
+
+impl NSECRecord {
+    pub(crate) fn new(name: DomainName, ttl: Option<u32>, class: &str, next_name: DomainName, types: Vec<String>) -> Self {
+        Self{ name, ttl, class: class.to_string(), next_name, types }
+    }
+
+    fn validate(&self) -> Result<(), ParseError> {
+        validate_types(&self.types)
+    }
+
+    fn rdata(&self) -> String {
+        std::iter::once(self.next_name.to_string())
+            .chain(self.types.iter().cloned())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Display for NSECRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), NSEC, self.rdata())
+    }
+}
+
+// ------------------------------------------ NSEC3Record ------------------------------------------
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct NSEC3Record {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // 1 for SHA-1, the only algorithm defined (RFC 5155 §11).
+    hash_algorithm: u8,
+    // 1 for Opt-Out.
+    flags: u8,
+    iterations: u16,
+    // hex, empty when written `-`.
+    salt: String,
+    // base32hex, without padding.
+    next_hashed_owner: String,
+    types: Vec<String>,
+}
+
+impl NSEC3Record {
+    fn validate(&self) -> Result<(), ParseError> {
+        validate_salt(&self.salt)?;
+        match from_base32hex(&self.next_hashed_owner) {
+            Ok(hash) if !hash.is_empty() && hash.len() <= MAX_CHARACTER_STRING => {}
+            _ => return Err(invalid_value(
+                &self.next_hashed_owner, "next hashed owner name should be a base32hex hash"
+            )),
+        }
+        validate_types(&self.types)
+    }
+
+    fn rdata(&self) -> String {
+        [self.hash_algorithm.to_string(), self.flags.to_string(), self.iterations.to_string()]
+            .into_iter()
+            .chain([format_salt(&self.salt), self.next_hashed_owner.to_string()])
+            .chain(self.types.iter().cloned())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl Display for NSEC3Record {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), NSEC3, self.rdata())
+    }
+}
+
+// ---------------------------------------- NSEC3PARAMRecord ---------------------------------------
+
+// NSEC3PARAMRecord holds the parameters used to hash the names of the zone, at its apex.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct NSEC3PARAMRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    hash_algorithm: u8,
+    flags: u8,
+    iterations: u16,
+    salt: String,
+}
+
+impl NSEC3PARAMRecord {
+    fn validate(&self) -> Result<(), ParseError> {
+        validate_salt(&self.salt)
+    }
+
+    fn rdata(&self) -> String {
+        format!("{} {} {} {}", self.hash_algorithm, self.flags, self.iterations, format_salt(&self.salt))
+    }
+}
+
+impl Display for NSEC3PARAMRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), NSEC3PARAM, self.rdata())
+    }
+}
+
+// parse_algorithm reads a DNSSEC algorithm number, or its mnemonic (RFC 4034 Appendix A.1).
+fn parse_algorithm(t: &Token) -> Result<u8, ParseError> {
+    let mnemonic = t.text.to_uppercase();
+    match ALGORITHMS.iter().find(|(m, _)| *m == mnemonic) {
+        Some((_, n)) => Ok(*n),
+        None => parse_u8(t, "algorithm"),
+    }
+}
+
+// parse_types reads the type list of NSEC records, validated by `validate_types`.
+fn parse_types(v: &[Token]) -> Vec<String> {
+    v.iter().map(|t| t.text.to_uppercase()).collect()
+}
+
+fn validate_types(types: &[String]) -> Result<(), ParseError> {
+    match types.iter().find(|t| type_code(t).is_none()) {
+        Some(t) => Err(ParseError::new(ParseErrorKind::UnknownType, t, "unknown record type".to_string())),
+        None => Ok(())
+    }
+}
+
+// join_tokens joins key material, hashes and signatures, which may span several tokens and lines.
+fn join_tokens(v: &[Token]) -> String {
+    v.iter().map(|t| t.text.as_str()).collect()
+}
+
+fn parse_base64(s: &str, field: &str) -> Result<Vec<u8>, ParseError> {
+    match from_base64(s) {
+        Ok(b) if !b.is_empty() => Ok(b),
+        _ => Err(invalid_value(s, &format!("{} should be valid, non-empty base64", field))),
+    }
+}
+
+fn parse_salt(t: &Token) -> String {
+    match t.text.as_str() {
+        "-" => String::new(),
+        s => s.to_uppercase(),
+    }
+}
+
+fn validate_salt(salt: &str) -> Result<(), ParseError> {
+    match from_hex(salt) {
+        Ok(b) if b.len() <= MAX_CHARACTER_STRING => Ok(()),
+        _ => Err(invalid_value(salt, "salt should be `-` or at most 255 bytes of hex")),
+    }
+}
+
+fn format_salt(salt: &str) -> String {
+    match salt {
+        "" => "-".to_string(),
+        s => s.to_string(),
+    }
+}
+
+// parse_timestamp reads a RRSIG timestamp written `YYYYMMDDHHmmSS` in UTC, or as seconds since
+// the unix epoch (RFC 4034 §3.2).
+fn parse_timestamp(t: &Token, field: &str) -> Result<u32, ParseError> {
+    let s = &t.text;
+    if s.len() != 14 {
+        return parse_u32(t, field)
+    }
+    let err = || invalid_value(s, &format!("{} should be written YYYYMMDDHHmmSS", field)).at_token(t);
+    let n = |range: std::ops::Range<usize>| s.get(range).and_then(|d| d.parse::<u32>().ok()).ok_or_else(err);
+    let (year, month, day) = (n(0..4)?, n(4..6)?, n(6..8)?);
+    let (hour, minute, second) = (n(8..10)?, n(10..12)?, n(12..14)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(err())
+    }
+    let days = serial::days_from_civil(i64::from(year), month, day);
+    let seconds = days * 86400 + i64::from(hour * 3600 + minute * 60 + second);
+    // timestamps are serial numbers, i.e. seconds modulo 2^32.
+    Ok(seconds.rem_euclid(1 << 32) as u32)
+}
+
+fn format_timestamp(seconds: u32) -> String {
+    let seconds = i64::from(seconds);
+    let (year, month, day) = serial::civil_from_days(seconds / 86400);
+    let time = seconds % 86400;
+    format!("{:04}{:02}{:02}{:02}{:02}{:02}", year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
+
+// ----------------------------------------- UnknownRecord -----------------------------------------
+
+// UnknownRecord holds a record of a type betterdns does not model, so that it survives being read
+// and written back (RFC 3597).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct UnknownRecord {
+    name: DomainName,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ttl: Option<u32>,
+    class: String,
+    // mnemonic of the type if known, `TYPEnnn` otherwise, e.g. `CAA` or `TYPE65534`.
+    record_type: String,
+    // hex, empty for empty rdata.
+    rdata: String,
+}
+
+impl UnknownRecord {
+    pub(crate) fn from_json(j: Json<Self>) -> Self {
+        let record = j.into_inner();
+        Self{
+            record_type: type_code(&record.record_type).map(type_name).unwrap_or(record.record_type),
+            rdata: record.rdata.to_uppercase(),
+            ..record
+        }
+    }
+
+    fn validate(&self) -> Result<(), ParseError> {
+        match type_code(&self.record_type) {
+            None => return Err(ParseError::new(ParseErrorKind::UnknownType, &self.record_type,
+                "record type should be a known mnemonic or written `TYPEnnn`".to_string()
+            )),
+            Some(code) if MODELED_TYPES.contains(&type_name(code).as_str()) => return Err(invalid_value(
+                &self.record_type, &format!("{} records should be written in their own format", type_name(code))
+            )),
+            Some(_) => {}
+        }
+        match from_hex(&self.rdata) {
+            Ok(rdata) if rdata.len() <= usize::from(u16::MAX) => Ok(()),
+            _ => Err(invalid_value(&self.rdata, "rdata should be at most 65535 bytes of hex")),
+        }
+    }
+
+    fn rdata(&self) -> String {
+        match self.rdata.len() {
+            0 => format!("{} 0", GENERIC_RDATA),
+            n => format!("{} {} {}", GENERIC_RDATA, n / 2, self.rdata),
+        }
+    }
+}
+
+impl Display for UnknownRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", format_owner(&self.name, self.ttl, &self.class), self.record_type, self.rdata())
+    }
+}
+
 // ----------------------------------------- OriginRecord ------------------------------------------
 
 
@@ -241,20 +1638,13 @@ pub(crate) struct OriginRecord {
 }
 
 impl OriginRecord {
-    pub(crate) fn from_str(s: &str) -> Result<Self, String> {
-        let origin = parse_origin(s)?;
-        Ok(Self{origin})
-    }
-
     pub(crate) fn new() -> Self {Self{ origin: "example.com".to_string() }}
-}
 
-fn parse_origin(s: &str) -> Result<String, String> {
-    if s.to_string().contains(ORIGIN) {
-        let s = s.replace(ORIGIN, "");
-        return Ok(s.replace(" ", ""))
+    pub(crate) fn from_name(origin: &str) -> Self {Self{ origin: origin.to_string() }}
+
+    pub(crate) fn get_origin(&self) -> String {
+        self.origin.to_string()
     }
-    Err("zonefile should contain ORIGIN".to_string())
 }
 
 impl Display for OriginRecord {
@@ -274,14 +1664,17 @@ pub(crate) struct TTLRecord {
 }
 
 impl TTLRecord {
-    pub(crate) fn from_str(s: &str) -> Self {
-        Self{
-            ttl: parse_ttl(s),
-        }
+    pub(crate) fn from_ttl(ttl: u32) -> Self {
+        Self{ ttl }
     }
+
     pub(crate) fn new() -> Self {
         Self{ ttl: 0 }
     }
+
+    pub(crate) fn get_ttl(&self) -> u32 {
+        self.ttl
+    }
 }
 
 impl Display for TTLRecord {
@@ -290,18 +1683,222 @@ impl Display for TTLRecord {
     }
 }
 
-fn parse_ttl(s: &str) -> u32 {
-    if s.to_string().contains(TTL) {
-        let s = s.replace(TTL, "");
-        let s = s.replace(" ", "");
-        return match s.parse::<u32>() {
-            Ok(u) => u,
-            Err(_) => {
-                println!("ttl should be a parseable u32 integer, received: {}", s);
-                DEFAULT_TTL
-            }
+/// parses a TTL expressed in seconds, or with BIND-style units, e.g. `1h30m` or `2w`.
+pub(crate) fn parse_ttl(s: &str) -> Result<u32, ParseError> {
+    if let Ok(ttl) = s.parse::<u32>() {
+        return Ok(ttl)
+    }
+
+    let err = || invalid_value(s, "ttl should be a parseable u32 integer or a duration, e.g. `1h30m`");
+    let (mut ttl, mut value): (u32, Option<u32>) = (0, None);
+    for c in s.chars() {
+        if let Some(d) = c.to_digit(10) {
+            let v = value.unwrap_or(0).checked_mul(10).and_then(|v| v.checked_add(d));
+            value = Some(v.ok_or_else(err)?);
+            continue
         }
+        let unit = match c.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            'w' => 604800,
+            _ => return Err(err())
+        };
+        let v = value.take().ok_or_else(err)?;
+        ttl = v.checked_mul(unit).and_then(|v| ttl.checked_add(v)).ok_or_else(err)?;
+    }
+    // trailing digits without unit are seconds.
+    ttl.checked_add(value.unwrap_or(0)).ok_or_else(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::serde::json;
+    use super::{CharacterString, MXRecord, RecordData, SRVRecord, TXTRecord, UnknownRecord, MAX_CHARACTER_STRING};
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    fn parse(soa: &str) -> Result<Manifest, String> {
+        let zone = format!("$ORIGIN example.com.\n$TTL 3600\n@ IN SOA {}\n", soa);
+        Manifest::parse(&zone, ParseMode::Strict)
+            .map(|(manifest, _)| manifest)
+            .map_err(|e| e.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"))
     }
-    println!("zonefile should contain TTL");
-    DEFAULT_TTL
-}
\ No newline at end of file
+
+    #[test]
+    fn soa_timers_accept_ttl_units() {
+        let manifest = parse("ns1 admin ( 1 1d 2h 4w 1h )").unwrap();
+        let soa = manifest.get_soa();
+        assert_eq!(
+            (soa.get_refresh(), soa.get_retry(), soa.get_expire(), soa.get_minimum()),
+            (86400, 7200, 2419200, 3600)
+        );
+    }
+
+    #[test]
+    fn invalid_soa_timers_are_reported() {
+        let e = parse("ns1 admin 1 7200 soon 1209600 3600").unwrap_err();
+        assert!(e.contains("retry should be"), "{}", e);
+    }
+
+    fn txt(rdata: &str) -> RecordData {
+        let zone = format!("$ORIGIN example.com.\n$TTL 3600\n@ IN SOA ns1 admin 1 2 3 4 5\nt IN TXT {}\n", rdata);
+        let (manifest, _) = Manifest::parse(&zone, ParseMode::Strict).expect("TXT record should parse");
+        manifest.get_records()[0].clone()
+    }
+
+    #[test]
+    fn character_strings_hold_any_byte() {
+        let RecordData::TXT(record) = txt(r#""a\200b" plain "\"q\" \\ \009 café""#) else { panic!("TXT record expected") };
+        let bytes: Vec<&[u8]> = record.strings.iter().map(|s| s.as_bytes()).collect();
+        assert_eq!(bytes, vec![&b"a\xc8b"[..], b"plain", "\"q\" \\ \t café".as_bytes()]);
+        assert_eq!(record.rdata(), r#""a\200b" "plain" "\"q\" \\ \009 café""#);
+        // the rendered record is parsed back as is.
+        assert_eq!(txt(&record.rdata()).to_string(), record.to_string());
+    }
+
+    #[test]
+    fn character_strings_are_json_strings_or_bytes() {
+        let text = CharacterString(b"v=spf1 -all".to_vec());
+        let binary = CharacterString(b"a\xc8b".to_vec());
+        assert_eq!(json::to_string(&text).unwrap(), r#""v=spf1 -all""#);
+        assert_eq!(json::to_string(&binary).unwrap(), "[97,200,98]");
+        assert_eq!(json::from_str::<CharacterString>(r#""v=spf1 -all""#).unwrap(), text);
+        assert_eq!(json::from_str::<CharacterString>("[97,200,98]").unwrap(), binary);
+    }
+
+    #[test]
+    fn long_character_strings_are_split_on_char_boundaries() {
+        let s = CharacterString(format!("{}é{}", "a".repeat(MAX_CHARACTER_STRING - 1), "b".repeat(300)).into_bytes());
+        let chunks = s.split();
+        assert!(chunks.iter().all(|c| c.0.len() <= MAX_CHARACTER_STRING && std::str::from_utf8(&c.0).is_ok()));
+        assert_eq!(chunks.iter().flat_map(|c| c.0.clone()).collect::<Vec<u8>>(), s.0);
+    }
+
+    #[test]
+    fn mx_and_srv_records_received_as_json_are_validated() {
+        let mx = |j: &str| RecordData::MX(json::from_str::<MXRecord>(j).unwrap()).validate();
+        assert!(mx(r#"{"name": "@", "class": "IN", "preference": 10, "exchange": "relay-1"}"#).is_ok());
+        assert!(mx(r#"{"name": "@", "class": "IN", "preference": 10, "exchange": "*.relay"}"#).is_err());
+
+        let srv = |j: &str| RecordData::SRV(json::from_str::<SRVRecord>(j).unwrap()).validate();
+        assert!(srv(r#"{"name": "_http._tcp.web", "class": "IN", "priority": 0, "weight": 5, "port": 80, "target": "web"}"#).is_ok());
+        assert!(srv(r#"{"name": "_http._tcp", "class": "IN", "priority": 0, "weight": 5, "port": 80, "target": "."}"#).is_ok());
+        assert!(srv(r#"{"name": "web", "class": "IN", "priority": 0, "weight": 5, "port": 80, "target": "web"}"#).is_err());
+        assert!(srv(r#"{"name": "_http._tcp.web", "class": "IN", "priority": 0, "weight": 5, "port": 80, "target": "*"}"#).is_err());
+    }
+
+    #[test]
+    fn records_received_as_json_have_their_type() {
+        let mx = r#""class": "IN", "preference": 10, "exchange": "relay-1""#;
+        let srv = r#""class": "IN", "priority": 0, "weight": 5, "port": 80, "target": "web""#;
+        let txt = r#""class": "IN", "strings": ["a"]"#;
+        for (name, record_type) in [("@", ""), ("@", r#""record_type": "mx","#)] {
+            assert!(json::from_str::<MXRecord>(&format!(r#"{{"name": "{}", {} {}}}"#, name, record_type, mx)).is_ok());
+        }
+        let e = json::from_str::<MXRecord>(&format!(r#"{{"name": "@", "record_type": "A", {}}}"#, mx)).err();
+        assert!(e.is_some_and(|e| e.to_string().contains("record_type should be equal to `MX`, received: `A`")));
+        assert!(json::from_str::<SRVRecord>(&format!(r#"{{"name": "_http._tcp", "record_type": "SRV", {}}}"#, srv)).is_ok());
+        assert!(json::from_str::<SRVRecord>(&format!(r#"{{"name": "_http._tcp", "record_type": "MX", {}}}"#, srv)).is_err());
+        assert!(json::from_str::<TXTRecord>(&format!(r#"{{"name": "@", "record_type": "TXT", {}}}"#, txt)).is_ok());
+        assert!(json::from_str::<TXTRecord>(&format!(r#"{{"name": "@", "record_type": "SPF", {}}}"#, txt)).is_err());
+
+        let record = json::from_str::<MXRecord>(&format!(r#"{{"name": "@", {}}}"#, mx)).unwrap();
+        assert!(json::to_string(&record).unwrap().contains(r#""record_type":"MX""#));
+    }
+
+    fn record(line: &str) -> Result<RecordData, String> {
+        let zone = format!("$ORIGIN example.com.\n$TTL 3600\n@ IN SOA ns1 admin 1 2 3 4 5\n{}\n", line);
+        Manifest::parse(&zone, ParseMode::Strict)
+            .map(|(manifest, _)| manifest.get_records()[0].clone())
+            .map_err(|e| e.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("\n"))
+    }
+
+    #[test]
+    fn dnssec_keys_and_signatures_span_several_lines() {
+        let dnskey = record("@ IN DNSKEY 257 3 ECDSAP256SHA256 (\n  AQID\n  BAUG )").unwrap();
+        assert_eq!(dnskey.get_rdata(), "257 3 13 AQIDBAUG");
+        let rrsig = record(
+            "@ IN RRSIG A 13 2 3600 20261101000000 1761955200 (\n  12345 example.com.\n  AQID BAUG )"
+        ).unwrap();
+        assert_eq!(rrsig.get_rdata(), "A 13 2 3600 20261101000000 20251101000000 12345 example.com. AQIDBAUG");
+
+        let e = record("@ IN DNSKEY 257 3 13 ( AQID\n %%%% )").unwrap_err();
+        assert!(e.contains("public key should be valid, non-empty base64"), "{}", e);
+        let e = record("@ IN DNSKEY 257 2 13 AQID").unwrap_err();
+        assert!(e.contains("protocol should be equal to 3"), "{}", e);
+        let e = record("@ IN RRSIG A 13 2 3600 20261301000000 20251101000000 12345 example.com. AQID").unwrap_err();
+        assert!(e.contains("expiration should be written YYYYMMDDHHmmSS"), "{}", e);
+        let e = record("@ IN RRSIG BOGUS 13 2 3600 20261101000000 20251101000000 12345 example.com. AQID").unwrap_err();
+        assert!(e.contains("unknown record type"), "{}", e);
+    }
+
+    #[test]
+    fn ds_digests_match_their_digest_type() {
+        for (digest_type, length) in [(1, 20), (2, 32), (4, 48)] {
+            let digest = "ab".repeat(length);
+            let ds = record(&format!("child IN DS 12345 13 {} {}", digest_type, digest)).unwrap();
+            assert_eq!(ds.get_rdata(), format!("12345 13 {} {}", digest_type, digest.to_uppercase()));
+            let e = record(&format!("child IN DS 12345 13 {} {}", digest_type, "ab".repeat(length + 1))).unwrap_err();
+            assert!(e.contains(&format!("should be {} bytes long", length)), "{}", e);
+        }
+        // the digest may be split, and digests of unassigned types are of any length.
+        assert!(record(&format!("child IN DS 12345 13 2 ( {}\n {} )", "ab".repeat(16), "cd".repeat(16))).is_ok());
+        assert!(record("child IN DS 12345 13 9 ABCD").is_ok());
+        assert!(record("child IN DS 12345 13 2 XYZ").is_err());
+    }
+
+    #[test]
+    fn nsec_records_list_the_types_of_their_owner() {
+        let nsec = record("@ IN NSEC a.example.com. a ns soa rrsig nsec dnskey").unwrap();
+        assert_eq!(nsec.get_rdata(), "a.example.com. A NS SOA RRSIG NSEC DNSKEY");
+        let nsec3 = record("h IN NSEC3 1 1 0 - 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR a rrsig TYPE65534").unwrap();
+        assert_eq!(nsec3.get_rdata(), "1 1 0 - 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR A RRSIG TYPE65534");
+        let nsec3 = record("h IN NSEC3 1 0 10 aabbccdd 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR").unwrap();
+        assert_eq!(nsec3.get_rdata(), "1 0 10 AABBCCDD 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR");
+        assert_eq!(record("@ IN NSEC3PARAM 1 0 0 -").unwrap().get_rdata(), "1 0 0 -");
+
+        let e = record("@ IN NSEC a.example.com. A BOGUS").unwrap_err();
+        assert!(e.contains("unknown record type"), "{}", e);
+        let e = record("h IN NSEC3 1 0 0 abc 2T7B4G4VSA5SMI47K61MV5BV1A22BOJR A").unwrap_err();
+        assert!(e.contains("salt should be `-`"), "{}", e);
+        let e = record("h IN NSEC3 1 0 0 - not-base32 A").unwrap_err();
+        assert!(e.contains("next hashed owner name should be a base32hex hash"), "{}", e);
+        assert!(record("@ IN NSEC3PARAM 1 0 0 - extra").is_err());
+    }
+
+    #[test]
+    fn unknown_types_are_kept_in_the_generic_form() {
+        let unknown = record("u IN TYPE65534 \\# 4 ( 0a00\n 0001 )").unwrap();
+        assert_eq!(unknown.to_string(), r"u IN TYPE65534 \# 4 0A000001");
+        assert_eq!(record(r"u IN caa \# 0").unwrap().to_string(), r"u IN CAA \# 0");
+        // modeled types written in the generic form are read in their own format.
+        assert_eq!(record(r"u IN A \# 4 0A000001").unwrap().to_string(), "u IN A 10.0.0.1");
+        // the written record is parsed back as is.
+        assert_eq!(record(&unknown.to_string()).unwrap().to_string(), unknown.to_string());
+
+        let e = record(r"u IN TYPE65534 \# 3 0A000001").unwrap_err();
+        assert!(e.contains("rdata should be 3 bytes long, received: 4"), "{}", e);
+        assert!(record(r"u IN TYPE65534 \# 2 0A0").is_err());
+        assert!(record(r"u IN TYPE65534 \# 2 0G00").is_err());
+        assert!(record(r"u IN TYPE65534 \#").is_err());
+        let e = record(r"u IN BOGUS \# 0").unwrap_err();
+        assert!(e.contains("known mnemonic or written `TYPEnnn`"), "{}", e);
+        assert!(record(r"u IN A \# 3 0A0000").is_err());
+    }
+
+    #[test]
+    fn unknown_records_received_as_json_are_validated() {
+        let unknown = |j: &str| UnknownRecord::from_json(rocket::serde::json::Json(json::from_str(j).unwrap()));
+        let record = unknown(r#"{"name": "u", "class": "IN", "record_type": "type257", "rdata": "00056973737565"}"#);
+        assert_eq!(record.to_string(), r"u IN CAA \# 7 00056973737565");
+        assert!(RecordData::Unknown(record).validate().is_ok());
+
+        let validate = |j: &str| RecordData::Unknown(unknown(j)).validate().map_err(|e| e.to_string());
+        let e = validate(r#"{"name": "u", "class": "IN", "record_type": "TYPE1", "rdata": "0A000001"}"#).unwrap_err();
+        assert!(e.contains("A records should be written in their own format"), "{}", e);
+        assert!(validate(r#"{"name": "u", "class": "IN", "record_type": "mx", "rdata": ""}"#).is_err());
+        assert!(validate(r#"{"name": "u", "class": "IN", "record_type": "BOGUS", "rdata": ""}"#).is_err());
+        assert!(validate(r#"{"name": "u", "class": "IN", "record_type": "TYPE65534", "rdata": "0A0"}"#).is_err());
+    }
+}
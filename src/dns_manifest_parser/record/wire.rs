@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::dns_manifest_parser::encoding::{from_base32hex, from_base64, from_hex, to_base32hex, to_base64, to_hex};
+use crate::dns_manifest_parser::error::{ParseError, ParseErrorKind};
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::record::{
+    type_code, type_name, CharacterString, DNSKEYRecord, DSRecord, MXRecord, NSEC3PARAMRecord, NSEC3Record,
+    NSECRecord, OriginRecord, RRSIGRecord, Record, RecordData, RecordType, SOARecord, SRVRecord, TXTRecord,
+    UnknownRecord, A, AAAA, CNAME, DNSKEY, DS, MAX_CHARACTER_STRING, MX, NS, NSEC, NSEC3, NSEC3PARAM, PTR, RRSIG,
+    SOA, SRV, TXT,
+};
+
+// CLASS values (RFC 1035 §3.2.4).
+const CLASSES: [(&str, u16); 4] = [("IN", 1), ("CS", 2), ("CH", 3), ("HS", 4)];
+
+// Compression pointers are 14-bit offsets flagged by their two high bits (RFC 1035 §4.1.4).
+const POINTER: u8 = 0xC0;
+const MAX_POINTER: usize = 0x3FFF;
+
+// ------------------------------------------- Encoder ---------------------------------------------
+
+/// Encoder writes resource records in wire format (RFC 1035 §4.1.3), compressing the owner names
+/// and the names of the rdata of well-known types (RFC 3597 §4). Offsets of compression pointers
+/// are relative to the start of the buffer, i.e. of the DNS message.
+#[derive(Default)]
+pub(crate) struct Encoder {
+    buf: Vec<u8>,
+    // offsets of the names already written, keyed by their lowercase labels.
+    names: HashMap<Vec<Vec<u8>>, u16>,
+    // canonical encoders write lowercase, uncompressed names (RFC 4034 §6.2).
+    canonical: bool,
+}
+
+impl Encoder {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// returns an encoder writing records in their canonical form, e.g. to be signed.
+    pub(crate) fn canonical() -> Self {
+        Self{ canonical: true, ..Self::default() }
+    }
+
+    /// appends a record; relative names are resolved against `origin` and `ttl` applies to
+    /// records without a TTL of their own.
+    pub(crate) fn record(&mut self, record: &RecordData, origin: &OriginRecord, ttl: u32) -> Result<&mut Self, ParseError> {
+        let record = record.resolve(origin);
+        self.name(record.get_name(), true)?;
+        self.u16(code(record.get_type())?);
+        self.u16(class_code(class(&record))?);
+        self.u32(record.get_ttl().unwrap_or(ttl));
+
+        let start = self.buf.len();
+        self.u16(0);
+        self.rdata(&record)?;
+
+        let length = u16::try_from(self.buf.len() - start - 2).map_err(|_| ParseError::new(
+            ParseErrorKind::InvalidValue, &record.to_string(), "rdata should be at most 65535 bytes long".to_string()
+        ))?;
+        self.buf[start..start + 2].copy_from_slice(&length.to_be_bytes());
+        Ok(self)
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    // rdata writes the rdata of an absolute record.
+    fn rdata(&mut self, record: &RecordData) -> Result<(), ParseError> {
+        match record {
+            RecordData::A(r) => self.buf.extend(r.value.octets()),
+            RecordData::AAAA(r) => self.buf.extend(r.value.octets()),
+            RecordData::CNAME(r) => self.name(&r.value, true)?,
+            RecordData::NS(r) => self.name(&r.value, true)?,
+            RecordData::PTR(r) => self.name(&r.value, true)?,
+            RecordData::MX(r) => {
+                self.u16(r.preference);
+                self.name(&r.exchange, true)?
+            }
+            RecordData::SOA(r) => {
+                self.name(&r.mname, true)?;
+                self.name(&r.rname, true)?;
+                for n in [r.serial, r.refresh, r.retry, r.expire, r.minimum] {
+                    self.u32(n)
+                }
+            }
+            // the target of SRV records is never compressed (RFC 2782).
+            RecordData::SRV(r) => {
+                for n in [r.priority, r.weight, r.port] {
+                    self.u16(n)
+                }
+                self.name(&r.target, false)?
+            }
+            RecordData::TXT(r) => {
+                for s in &r.strings {
+                    self.character_string(s)?
+                }
+            }
+            // names of DNSSEC records are never compressed (RFC 4034 §4.1.1, §3.1.7).
+            RecordData::DNSKEY(r) => {
+                self.u16(r.flags);
+                self.buf.extend([r.protocol, r.algorithm]);
+                self.buf.extend(decode(&r.public_key, from_base64(&r.public_key))?)
+            }
+            RecordData::DS(r) => {
+                self.u16(r.key_tag);
+                self.buf.extend([r.algorithm, r.digest_type]);
+                self.buf.extend(decode(&r.digest, from_hex(&r.digest))?)
+            }
+            RecordData::RRSIG(r) => {
+                self.u16(code(&r.type_covered)?);
+                self.buf.extend([r.algorithm, r.labels]);
+                for n in [r.original_ttl, r.expiration, r.inception] {
+                    self.u32(n)
+                }
+                self.u16(r.key_tag);
+                self.name(&r.signer_name, false)?;
+                self.buf.extend(decode(&r.signature, from_base64(&r.signature))?)
+            }
+            RecordData::NSEC(r) => {
+                self.name(&r.next_name, false)?;
+                self.type_bitmap(&r.types)?
+            }
+            RecordData::NSEC3(r) => {
+                self.nsec3_parameters(r.hash_algorithm, r.flags, r.iterations, &r.salt)?;
+                let hash = decode(&r.next_hashed_owner, from_base32hex(&r.next_hashed_owner))?;
+                self.buf.push(hash.len() as u8);
+                self.buf.extend(hash);
+                self.type_bitmap(&r.types)?
+            }
+            RecordData::NSEC3PARAM(r) => self.nsec3_parameters(r.hash_algorithm, r.flags, r.iterations, &r.salt)?,
+            RecordData::Unknown(r) => self.buf.extend(decode(&r.rdata, from_hex(&r.rdata))?),
+        }
+        Ok(())
+    }
+
+    // name writes an absolute name, ending with a pointer to the longest suffix already written.
+    fn name(&mut self, name: &DomainName, compress: bool) -> Result<(), ParseError> {
+        if self.canonical {
+            for label in canonical_labels(name)? {
+                self.buf.push(label.len() as u8);
+                self.buf.extend(label);
+            }
+            self.buf.push(0);
+            return Ok(())
+        }
+        let labels = labels(name)?;
+        for i in 0..labels.len() {
+            let suffix: Vec<Vec<u8>> = labels[i..].iter().map(|l| l.to_ascii_lowercase()).collect();
+            match self.names.get(&suffix) {
+                Some(&offset) if compress => {
+                    self.u16(offset | u16::from(POINTER) << 8);
+                    return Ok(())
+                }
+                Some(_) => {}
+                None if self.buf.len() <= MAX_POINTER => {
+                    self.names.insert(suffix, self.buf.len() as u16);
+                }
+                None => {}
+            }
+            self.buf.push(labels[i].len() as u8);
+            self.buf.extend(&labels[i]);
+        }
+        self.buf.push(0);
+        Ok(())
+    }
+
+    fn character_string(&mut self, s: &CharacterString) -> Result<(), ParseError> {
+        let bytes = s.as_bytes();
+        if bytes.len() > MAX_CHARACTER_STRING {
+            return Err(ParseError::new(ParseErrorKind::InvalidValue, &s.to_string(), format!(
+                "character-string should be at most {} bytes long, received: {}", MAX_CHARACTER_STRING, bytes.len()
+            )))
+        }
+        self.buf.push(bytes.len() as u8);
+        self.buf.extend(bytes);
+        Ok(())
+    }
+
+    fn nsec3_parameters(&mut self, hash_algorithm: u8, flags: u8, iterations: u16, salt: &str) -> Result<(), ParseError> {
+        let salt = decode(salt, from_hex(salt))?;
+        self.buf.extend([hash_algorithm, flags]);
+        self.u16(iterations);
+        self.buf.push(salt.len() as u8);
+        self.buf.extend(salt);
+        Ok(())
+    }
+
+    // type_bitmap writes the types present at a name, in windows of 256 types (RFC 4034 §4.1.2).
+    fn type_bitmap(&mut self, types: &[String]) -> Result<(), ParseError> {
+        let mut codes = types.iter().map(|t| code(t)).collect::<Result<Vec<u16>, ParseError>>()?;
+        codes.sort_unstable();
+        codes.dedup();
+        for window in 0..=u8::MAX {
+            let low: Vec<u8> = codes.iter().filter(|&&c| (c >> 8) as u8 == window).map(|&c| c as u8).collect();
+            let Some(&last) = low.last() else { continue };
+            let mut bitmap = vec![0u8; last as usize / 8 + 1];
+            for b in low {
+                bitmap[b as usize / 8] |= 0x80 >> (b % 8);
+            }
+            self.buf.extend([window, bitmap.len() as u8]);
+            self.buf.extend(bitmap);
+        }
+        Ok(())
+    }
+
+    fn u16(&mut self, n: u16) {
+        self.buf.extend(n.to_be_bytes())
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.buf.extend(n.to_be_bytes())
+    }
+}
+
+// ------------------------------------------- Decoder ---------------------------------------------
+
+/// Decoder reads resource records in wire format from a DNS message, following compression
+/// pointers. Decoded records have absolute names and an explicit TTL.
+pub(crate) struct Decoder<'a> {
+    message: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    /// reads `message` from `position`, e.g. past the header and question of a DNS message.
+    pub(crate) fn new(message: &'a [u8], position: usize) -> Self {
+        Self{ message, position }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.position >= self.message.len()
+    }
+
+    pub(crate) fn record(&mut self) -> Result<RecordData, ParseError> {
+        let name = self.name()?;
+        let type_code = self.u16()?;
+        let class = class_name(self.u16()?);
+        let ttl = Some(self.u32()?);
+        let length = self.u16()? as usize;
+        self.rdata(name, ttl, class, type_code, length)
+    }
+
+    // rdata reads the `length` bytes of rdata of a record.
+    fn rdata(
+        &mut self,
+        name: DomainName,
+        ttl: Option<u32>,
+        class: String,
+        type_code: u16,
+        length: usize,
+    ) -> Result<RecordData, ParseError> {
+        let end = self.position + length;
+        if end > self.message.len() {
+            return Err(self.error("rdata is truncated"))
+        }
+
+        let record = match type_name(type_code).as_str() {
+            A => RecordData::A(Record::new(name, ttl, &class, Ipv4Addr::from(self.u32()?))),
+            AAAA => {
+                let octets: [u8; 16] = self.bytes(16)?.try_into().expect("16 bytes were read");
+                RecordData::AAAA(Record::new(name, ttl, &class, Ipv6Addr::from(octets)))
+            }
+            CNAME => RecordData::CNAME(Record::new(name, ttl, &class, self.name()?)),
+            NS => RecordData::NS(Record::new(name, ttl, &class, self.name()?)),
+            PTR => RecordData::PTR(Record::new(name, ttl, &class, self.name()?)),
+            MX => RecordData::MX(MXRecord{
+                name, ttl, class,
+                record_type: RecordType::default(),
+                preference: self.u16()?,
+                exchange: self.name()?,
+            }),
+            SOA => RecordData::SOA(SOARecord{
+                name, ttl, class,
+                mname: self.name()?,
+                rname: self.name()?,
+                serial: self.u32()?,
+                refresh: self.u32()?,
+                retry: self.u32()?,
+                expire: self.u32()?,
+                minimum: self.u32()?,
+            }),
+            SRV => RecordData::SRV(SRVRecord{
+                name, ttl, class,
+                record_type: RecordType::default(),
+                priority: self.u16()?,
+                weight: self.u16()?,
+                port: self.u16()?,
+                target: self.name()?,
+            }),
+            TXT => {
+                let mut strings: Vec<CharacterString> = vec![];
+                while self.position < end {
+                    strings.push(CharacterString::from(self.length_prefixed()?));
+                }
+                RecordData::TXT(TXTRecord{ name, ttl, class, record_type: RecordType::default(), strings })
+            }
+            DNSKEY => RecordData::DNSKEY(DNSKEYRecord{
+                name, ttl, class,
+                flags: self.u16()?,
+                protocol: self.u8()?,
+                algorithm: self.u8()?,
+                public_key: to_base64(self.rest(end)?),
+            }),
+            DS => RecordData::DS(DSRecord{
+                name, ttl, class,
+                key_tag: self.u16()?,
+                algorithm: self.u8()?,
+                digest_type: self.u8()?,
+                digest: to_hex(self.rest(end)?),
+            }),
+            RRSIG => RecordData::RRSIG(RRSIGRecord{
+                name, ttl, class,
+                type_covered: type_name(self.u16()?),
+                algorithm: self.u8()?,
+                labels: self.u8()?,
+                original_ttl: self.u32()?,
+                expiration: self.u32()?,
+                inception: self.u32()?,
+                key_tag: self.u16()?,
+                signer_name: self.name()?,
+                signature: to_base64(self.rest(end)?),
+            }),
+            NSEC => RecordData::NSEC(NSECRecord{
+                name, ttl, class,
+                next_name: self.name()?,
+                types: self.type_bitmap(end)?,
+            }),
+            NSEC3 => RecordData::NSEC3(NSEC3Record{
+                name, ttl, class,
+                hash_algorithm: self.u8()?,
+                flags: self.u8()?,
+                iterations: self.u16()?,
+                salt: to_hex(self.length_prefixed()?),
+                next_hashed_owner: to_base32hex(self.length_prefixed()?),
+                types: self.type_bitmap(end)?,
+            }),
+            NSEC3PARAM => RecordData::NSEC3PARAM(NSEC3PARAMRecord{
+                name, ttl, class,
+                hash_algorithm: self.u8()?,
+                flags: self.u8()?,
+                iterations: self.u16()?,
+                salt: to_hex(self.length_prefixed()?),
+            }),
+            record_type => RecordData::Unknown(UnknownRecord{
+                name, ttl, class,
+                record_type: record_type.to_string(),
+                rdata: to_hex(self.rest(end)?),
+            }),
+        };
+
+        if self.position != end {
+            return Err(self.error(&format!("rdata should be {} bytes long", length)))
+        }
+        Ok(record)
+    }
+
+    // name reads a name, which may end with a pointer to a prior occurrence of its suffix.
+    // Each pointer must point before the previous one, so that they cannot loop.
+    fn name(&mut self) -> Result<DomainName, ParseError> {
+        let mut labels: Vec<Vec<u8>> = vec![];
+        let mut position = self.position;
+        let mut limit = self.position;
+        let mut jumped = false;
+        loop {
+            let length = *self.message.get(position).ok_or_else(|| self.error("name is truncated"))?;
+            match length & POINTER {
+                0 if length == 0 => {
+                    position += 1;
+                    break
+                }
+                0 => {
+                    let label = self.message
+                        .get(position + 1..position + 1 + length as usize)
+                        .ok_or_else(|| self.error("label is truncated"))?;
+                    labels.push(label.to_vec());
+                    position += 1 + length as usize;
+                }
+                POINTER => {
+                    let low = *self.message.get(position + 1).ok_or_else(|| self.error("pointer is truncated"))?;
+                    let pointer = usize::from(length & !POINTER) << 8 | usize::from(low);
+                    if pointer >= limit {
+                        return Err(self.error("compression pointer should point backward"))
+                    }
+                    limit = pointer;
+                    if !jumped {
+                        self.position = position + 2;
+                        jumped = true;
+                    }
+                    position = pointer;
+                }
+                _ => return Err(self.error("unsupported label type")),
+            }
+        }
+        if !jumped {
+            self.position = position;
+        }
+        DomainName::parse(&name_text(&labels))
+    }
+
+    fn type_bitmap(&mut self, end: usize) -> Result<Vec<String>, ParseError> {
+        let mut types: Vec<String> = vec![];
+        while self.position < end {
+            let window = u16::from(self.u8()?);
+            let bitmap = self.length_prefixed()?;
+            for (i, byte) in bitmap.iter().enumerate() {
+                for bit in (0..8).filter(|bit| byte & 0x80 >> bit != 0) {
+                    types.push(type_name(window << 8 | (i * 8 + bit) as u16))
+                }
+            }
+        }
+        Ok(types)
+    }
+
+    // rest reads the remaining bytes of the rdata ending at `end`.
+    fn rest(&mut self, end: usize) -> Result<&'a [u8], ParseError> {
+        match end.checked_sub(self.position) {
+            Some(n) => self.bytes(n),
+            None => Err(self.error("rdata overflows its length")),
+        }
+    }
+
+    fn length_prefixed(&mut self) -> Result<&'a [u8], ParseError> {
+        let length = self.u8()? as usize;
+        self.bytes(length)
+    }
+
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        let bytes = self.message
+            .get(self.position..self.position + n)
+            .ok_or_else(|| self.error("message is truncated"))?;
+        self.position += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ParseError> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().expect("2 bytes were read")))
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().expect("4 bytes were read")))
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(ParseErrorKind::Wire, &format!("offset {}", self.position), message.to_string())
+    }
+}
+
+/// reads the rdata of a record in wire format, e.g. written in the generic form of RFC 3597 §5.
+/// Names must not be compressed.
+pub(crate) fn decode_rdata(
+    name: DomainName,
+    ttl: Option<u32>,
+    class: &str,
+    type_code: u16,
+    rdata: &[u8],
+) -> Result<RecordData, ParseError> {
+    Decoder::new(rdata, 0).rdata(name, ttl, class.to_string(), type_code, rdata.len())
+}
+
+// ------------------------------------------- Signing ---------------------------------------------
+
+/// returns the data signed by `rrsig` (RFC 4034 §3.1.8.1): its rdata without the signature,
+/// followed by the records of `rrset` in canonical form and order.
+pub(crate) fn signed_data(rrsig: &RRSIGRecord, rrset: &[RecordData], origin: &OriginRecord) -> Result<Vec<u8>, ParseError> {
+    let mut rdatas = rrset
+        .iter()
+        .map(|record| canonical_rdata(record, origin))
+        .collect::<Result<Vec<Vec<u8>>, ParseError>>()?;
+    rdatas.sort();
+    rdatas.dedup();
+
+    let mut encoder = Encoder::canonical();
+    encoder.rdata(&RecordData::RRSIG(RRSIGRecord{ signature: String::new(), ..rrsig.clone() }))?;
+    let Some(record) = rrset.first() else { return Ok(encoder.finish()) };
+    let owner = record.get_name().resolve(origin);
+    let class = class_code(class(record))?;
+    for rdata in rdatas {
+        encoder.name(&owner, false)?;
+        encoder.u16(code(&rrsig.type_covered)?);
+        encoder.u16(class);
+        encoder.u32(rrsig.original_ttl);
+        encoder.u16(rdata.len() as u16);
+        encoder.buf.extend(rdata);
+    }
+    Ok(encoder.finish())
+}
+
+/// returns the rdata of `record` in canonical form (RFC 4034 §6.2), its names resolved against
+/// `origin`.
+pub(crate) fn canonical_rdata(record: &RecordData, origin: &OriginRecord) -> Result<Vec<u8>, ParseError> {
+    let mut encoder = Encoder::canonical();
+    encoder.rdata(&record.resolve(origin))?;
+    Ok(encoder.finish())
+}
+
+/// returns the lowercase labels of an absolute name. Names sort in canonical order (RFC 4034 §6.1)
+/// by their labels read from the root.
+pub(crate) fn canonical_labels(name: &DomainName) -> Result<Vec<Vec<u8>>, ParseError> {
+    Ok(labels(name)?.iter().map(|l| l.to_ascii_lowercase()).collect())
+}
+
+// ------------------------------------------- helpers ---------------------------------------------
+
+fn class(record: &RecordData) -> &str {
+    match record {
+        RecordData::A(r) => &r.class,
+        RecordData::AAAA(r) => &r.class,
+        RecordData::CNAME(r) => &r.class,
+        RecordData::MX(r) => &r.class,
+        RecordData::NS(r) => &r.class,
+        RecordData::PTR(r) => &r.class,
+        RecordData::SOA(r) => &r.class,
+        RecordData::SRV(r) => &r.class,
+        RecordData::TXT(r) => &r.class,
+        RecordData::DNSKEY(r) => &r.class,
+        RecordData::DS(r) => &r.class,
+        RecordData::RRSIG(r) => &r.class,
+        RecordData::NSEC(r) => &r.class,
+        RecordData::NSEC3(r) => &r.class,
+        RecordData::NSEC3PARAM(r) => &r.class,
+        RecordData::Unknown(r) => &r.class,
+    }
+}
+
+fn code(name: &str) -> Result<u16, ParseError> {
+    type_code(name).ok_or_else(|| ParseError::new(ParseErrorKind::UnknownType, name, "unknown record type".to_string()))
+}
+
+// decode maps the errors of binary-to-text decodings, which records are validated against.
+fn decode(s: &str, result: Result<Vec<u8>, String>) -> Result<Vec<u8>, ParseError> {
+    result.map_err(|e| ParseError::new(ParseErrorKind::InvalidValue, s, e))
+}
+
+// class_code also reads the generic form of classes, e.g. `CLASS32` (RFC 3597 §5).
+fn class_code(name: &str) -> Result<u16, ParseError> {
+    let name = name.to_ascii_uppercase();
+    match CLASSES.iter().find(|(c, _)| *c == name) {
+        Some((_, code)) => Ok(*code),
+        None => name
+            .strip_prefix("CLASS")
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidValue, &name, "unknown class".to_string())),
+    }
+}
+
+fn class_name(code: u16) -> String {
+    match CLASSES.iter().find(|(_, c)| *c == code) {
+        Some((name, _)) => name.to_string(),
+        None => format!("CLASS{}", code),
+    }
+}
+
+// labels splits an absolute name into its labels, unescaping `\X` and `\DDD`.
+fn labels(name: &DomainName) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut labels: Vec<Vec<u8>> = vec![];
+    let mut label: Vec<u8> = vec![];
+    let mut bytes = name.as_str().bytes();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'.' if label.is_empty() && labels.is_empty() => {}
+            b'.' => labels.push(std::mem::take(&mut label)),
+            b'\\' => match bytes.next() {
+                Some(d) if d.is_ascii_digit() => {
+                    let digits: Vec<u8> = [Some(d), bytes.next(), bytes.next()].into_iter().flatten().collect();
+                    let n = std::str::from_utf8(&digits).ok().and_then(|s| s.parse::<u8>().ok());
+                    label.push(n.ok_or_else(|| ParseError::new(
+                        ParseErrorKind::InvalidValue, name.as_str(), "invalid `\\DDD` escape".to_string()
+                    ))?)
+                }
+                Some(c) => label.push(c),
+                None => return Err(ParseError::new(
+                    ParseErrorKind::InvalidValue, name.as_str(), "dangling escape".to_string()
+                )),
+            },
+            b => label.push(b),
+        }
+    }
+    if !label.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::InvalidValue, name.as_str(), "name should be absolute".to_string()))
+    }
+    Ok(labels)
+}
+
+// name_text writes labels in zone format, escaping the bytes which are not letters, digits, `-`
+// or `_` as `\DDD`.
+fn name_text(labels: &[Vec<u8>]) -> String {
+    if labels.is_empty() {
+        return ".".to_string()
+    }
+    labels
+        .iter()
+        .map(|label| match label.as_slice() {
+            b"*" => "*".to_string(),
+            _ => label
+                .iter()
+                .map(|&b| match b {
+                    b if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' => (b as char).to_string(),
+                    b => format!("\\{:03}", b),
+                })
+                .collect(),
+        })
+        .map(|label| label + ".")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, Encoder};
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+    use crate::dns_manifest_parser::record::RecordData;
+
+    const KEY: &str = "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4OTo7PD0+Pw==";
+
+    fn zone() -> Manifest {
+        let zone = format!("$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin.example.com. 2022101100 7200 3600 1209600 300
+@ IN NS ns1
+@ 60 IN MX 10 mail
+ns1 IN A 10.0.0.1
+ns1 IN AAAA fd00::1
+www IN CNAME ns1
+1.0.0.10.in-addr.arpa. IN PTR ns1
+_sip._tcp IN SRV 10 60 5060 sip.example.org.
+txt IN TXT \"hello world\" \"\" \"quote\\\"d\" \"a\\200b\"
+@ IN DNSKEY 257 3 13 {key}
+sub IN DS 12345 13 2 0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF
+@ IN RRSIG A 13 2 3600 20261101000000 20261018000000 12345 example.com. {key}
+@ IN NSEC www.example.com. A NS SOA MX RRSIG NSEC DNSKEY TYPE65534
+0p9mhaveqvm6t7vbl5lop2u3t2rp3tom IN NSEC3 1 0 10 AABBCCDD 2t7b4g4vsa5smi47k61mv5bv1a22bojr A RRSIG
+@ IN NSEC3PARAM 1 0 10 -
+probe IN TYPE65534 \\# 4 0A000001
+", key = KEY);
+        Manifest::parse(&zone, ParseMode::Strict).expect("zone should parse").0
+    }
+
+    fn encode(records: &[RecordData]) -> Vec<u8> {
+        let manifest = zone();
+        let mut encoder = Encoder::new();
+        for r in records {
+            encoder.record(r, manifest.get_origin(), 3600).expect("record should encode");
+        }
+        encoder.finish()
+    }
+
+    #[test]
+    fn every_record_type_round_trips() {
+        let manifest = zone();
+        let mut records = manifest.get_records().clone();
+        records.push(RecordData::SOA(manifest.get_soa().clone()));
+        assert!(records.len() >= 16, "every variant of RecordData should be covered");
+
+        for record in records.iter() {
+            let wire = encode(std::slice::from_ref(record));
+            let decoded = Decoder::new(&wire, 0).record()
+                .unwrap_or_else(|e| panic!("{} should decode: {}", record, e));
+            let expected = record.resolve(manifest.get_origin());
+
+            assert_eq!(decoded.get_name(), expected.get_name(), "{}", record);
+            assert_eq!(decoded.get_type(), expected.get_type(), "{}", record);
+            assert_eq!(decoded.get_rdata(), expected.get_rdata(), "{}", record);
+            assert_eq!(decoded.get_ttl(), Some(expected.get_ttl().unwrap_or(3600)), "{}", record);
+            assert_eq!(encode(&[decoded]), wire, "{}", record);
+        }
+    }
+
+    #[test]
+    fn names_are_compressed() {
+        let manifest = zone();
+        let records: Vec<RecordData> = manifest.get_records()
+            .iter()
+            .filter(|r| r.get_name().as_str() == "ns1" || r.get_name().as_str() == "www")
+            .cloned()
+            .collect();
+        let wire = encode(&records);
+
+        // `ns1.example.com.` is written once, then pointed to from offset 0.
+        let name_length = "\x03ns1\x07example\x03com\x00".len();
+        let first = name_length + 10 + 4;
+        assert_eq!(wire[first..first + 2], [0xC0, 0x00]);
+        // the CNAME of `www` points to `ns1.example.com.` too.
+        assert_eq!(wire[wire.len() - 2..], [0xC0, 0x00]);
+
+        let mut decoder = Decoder::new(&wire, 0);
+        let mut decoded: Vec<String> = vec![];
+        while !decoder.is_empty() {
+            decoded.push(decoder.record().unwrap().to_string());
+        }
+        let expected: Vec<String> = records
+            .iter()
+            .map(|r| r.resolve(manifest.get_origin()).to_string().replace(" IN ", " 3600 IN "))
+            .collect();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn truncated_messages_are_rejected() {
+        let manifest = zone();
+        for record in manifest.get_records() {
+            let wire = encode(std::slice::from_ref(record));
+            for end in 0..wire.len() {
+                assert!(Decoder::new(&wire[..end], 0).record().is_err(), "{} truncated at {}", record, end);
+            }
+        }
+    }
+
+    #[test]
+    fn malformed_messages_are_rejected() {
+        // forward and looping pointers.
+        assert!(Decoder::new(&[0xC0, 0x02, 0x00], 0).record().is_err());
+        assert!(Decoder::new(&[0xC0, 0x00], 0).record().is_err());
+        // a pointer to a label followed by a pointer back to that label.
+        assert!(Decoder::new(&[0x01, b'a', 0xC0, 0x00, 0xC0, 0x00], 4).record().is_err());
+        // reserved label type.
+        assert!(Decoder::new(&[0x40, 0x00], 0).record().is_err());
+        // rdata longer than its RDLENGTH: an A record of 5 bytes.
+        let a = [0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 5, 10, 0, 0, 1, 0];
+        assert!(Decoder::new(&a, 0).record().is_err());
+        // rdata shorter than its RDLENGTH: an A record of 3 bytes.
+        let a = [0, 0, 1, 0, 1, 0, 0, 0, 60, 0, 3, 10, 0, 0];
+        assert!(Decoder::new(&a, 0).record().is_err());
+    }
+
+    #[test]
+    fn arbitrary_bytes_do_not_panic() {
+        // xorshift, to cover the decoder with reproducible garbage.
+        let mut state: u32 = 0x9E37_79B9;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state
+        };
+        let wire = encode(zone().get_records());
+        for _ in 0..2000 {
+            let mut message = wire.clone();
+            for _ in 0..4 {
+                let i = next() as usize % message.len();
+                message[i] = next() as u8;
+            }
+            let mut decoder = Decoder::new(&message, 0);
+            while !decoder.is_empty() {
+                if decoder.record().is_err() {
+                    break
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use crate::dns_manifest_parser::manifest::Manifest;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::record::{NSRdata, OriginRecord, Record, RecordData};
+use crate::dns_manifest_parser::serial;
+
+const IN_ADDR_ARPA: &str = "in-addr.arpa.";
+const IP6_ARPA: &str = "ip6.arpa.";
+
+// Number of nibbles of an IPv6 address delegated to a reverse zone (/64).
+const IP6_ZONE_NIBBLES: usize = 16;
+
+/// derives the reverse zones of a forward manifest: one `in-addr.arpa.` zone per /24 holding
+/// its A records and one `ip6.arpa.` zone per /64 holding its AAAA records.
+/// Reverse zones reuse the SOA, TTL and apex NS records of the forward zone. Wildcards name no
+/// host: their addresses are left out.
+pub(crate) fn reverse_manifests(forward: &Manifest) -> Vec<Manifest> {
+    let origin = forward.get_origin();
+
+    let mut zones: BTreeMap<String, Vec<RecordData>> = BTreeMap::new();
+    for record in forward.get_records().iter().filter(|r| !r.get_name().is_wildcard()) {
+        let (ttl, class, (zone, owner)) = match record {
+            RecordData::A(r) => (r.get_ttl(), r.get_class(), reverse_ipv4(*r.get_value())),
+            RecordData::AAAA(r) => (r.get_ttl(), r.get_class(), reverse_ipv6(*r.get_value())),
+            _ => continue
+        };
+        let ptr = Record::new(
+            DomainName::new_unchecked(&owner), ttl, &class, record.get_name().resolve(origin)
+        );
+        let records = zones.entry(zone).or_default();
+        if !records.iter().any(|p| p.to_string() == ptr.to_string()) {
+            records.push(RecordData::PTR(ptr));
+        }
+    }
+
+    let name_servers: Vec<&Record<NSRdata>> = forward.get_records()
+        .iter()
+        .filter_map(|r| match r {
+            RecordData::NS(ns) if r.get_name().resolve(origin).as_str() == origin.get_origin() => Some(ns),
+            _ => None
+        })
+        .collect();
+
+    zones
+        .into_iter()
+        .map(|(zone, ptrs)| {
+            let name = DomainName::new_unchecked(&zone);
+            let mut records: Vec<RecordData> = name_servers
+                .iter()
+                .map(|ns| RecordData::NS(
+                    Record::new(name.clone(), ns.get_ttl(), &ns.get_class(), ns.get_value().resolve(origin))
+                ))
+                .collect();
+            records.extend(ptrs);
+
+            Manifest::from_parts(
+                OriginRecord::from_name(&zone),
+                forward.get_ttl().clone(),
+                forward.get_soa().for_zone(&name, origin),
+                records,
+            )
+        })
+        .collect()
+}
+
+/// merges reverse zones derived from several forward manifests, e.g. two forward zones with
+/// addresses in the same /24. The first of them provides the SOA, TTL and NS records, the SOA
+/// taking the latest serial of them so that it moves forward whichever forward zone changes.
+pub(crate) fn merge_reverse_manifests(zones: Vec<Manifest>) -> Vec<Manifest> {
+    let mut merged: BTreeMap<String, Manifest> = BTreeMap::new();
+    for zone in zones {
+        let origin = zone.get_origin().get_origin();
+        let Some(first) = merged.remove(&origin) else {
+            merged.insert(origin, zone);
+            continue
+        };
+        let mut records = first.get_records().clone();
+        for ptr in zone.get_records().iter().filter(|r| matches!(r, RecordData::PTR(_))) {
+            if !records.iter().any(|r| r.to_string() == ptr.to_string()) {
+                records.push(ptr.clone());
+            }
+        }
+        let (serial, other) = (first.get_soa().get_serial(), zone.get_soa().get_serial());
+        let serial = if serial::is_after(other, serial) { other } else { serial };
+        merged.insert(origin, Manifest::from_parts(
+            first.get_origin().clone(), first.get_ttl().clone(), first.get_soa().with_serial(serial), records
+        ));
+    }
+    merged.into_values().collect()
+}
+
+/// keeps the serial of a reverse zone moving forward from `previous`, the version last written:
+/// a zone with the same content keeps its serial, and a changed zone whose derived serial does
+/// not come after it, e.g. when a forward zone with a lower serial changes, increments it.
+pub(crate) fn follow_serial(zone: Manifest, previous: &Manifest) -> Manifest {
+    let soa = zone.get_soa();
+    let (derived, last) = (soa.get_serial(), previous.get_soa().get_serial());
+    let with_serial = |serial: u32| Manifest::from_parts(
+        zone.get_origin().clone(), zone.get_ttl().clone(), soa.with_serial(serial), zone.get_records().clone()
+    );
+    let unchanged = with_serial(last);
+    if unchanged.to_string() == previous.to_string() {
+        return unchanged
+    }
+    with_serial(if serial::is_after(derived, last) { derived } else { serial::add(last, 1) })
+}
+
+// reverse_ipv4 returns the /24 reverse zone of `ip` and the owner name of its PTR record.
+fn reverse_ipv4(ip: Ipv4Addr) -> (String, String) {
+    let [a, b, c, d] = ip.octets();
+    (format!("{}.{}.{}.{}", c, b, a, IN_ADDR_ARPA), d.to_string())
+}
+
+// reverse_ipv6 returns the /64 reverse zone of `ip` and the owner name of its PTR record.
+fn reverse_ipv6(ip: Ipv6Addr) -> (String, String) {
+    let nibbles: Vec<String> = ip.octets()
+        .iter()
+        .flat_map(|b| [b >> 4, b & 0xf])
+        .map(|n| format!("{:x}", n))
+        .collect();
+    let (network, host) = nibbles.split_at(IP6_ZONE_NIBBLES);
+    let reversed = |v: &[String]| v.iter().rev().cloned().collect::<Vec<String>>().join(".");
+
+    (format!("{}.{}", reversed(network), IP6_ARPA), reversed(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{follow_serial, merge_reverse_manifests, reverse_manifests};
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+@ IN NS ns1
+ns1 IN A 10.0.0.1
+web IN A 10.0.0.5
+www IN A 10.0.0.5
+db 300 IN A 10.0.1.7
+v6 IN AAAA 2001:db8::1
+*.dev IN A 10.128.0.50
+* IN AAAA 2001:db8::2
+";
+
+    fn reverse(zone: &str) -> Vec<(String, Vec<String>)> {
+        let (manifest, _) = Manifest::parse(zone, ParseMode::Strict).expect("manifest should parse");
+        zones(reverse_manifests(&manifest))
+    }
+
+    fn zones(manifests: Vec<Manifest>) -> Vec<(String, Vec<String>)> {
+        manifests
+            .iter()
+            .map(|m| (m.get_origin().get_origin(), m.get_records().iter().map(|r| r.to_string()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn addresses_are_reversed() {
+        // zones are sorted by origin.
+        assert_eq!(reverse(ZONE), vec![
+            ("0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa.".to_string(), vec![
+                "0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa. IN NS ns1.example.com.".to_string(),
+                "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0 IN PTR v6.example.com.".to_string(),
+            ]),
+            ("0.0.10.in-addr.arpa.".to_string(), vec![
+                "0.0.10.in-addr.arpa. IN NS ns1.example.com.".to_string(),
+                "1 IN PTR ns1.example.com.".to_string(),
+                "5 IN PTR web.example.com.".to_string(),
+                "5 IN PTR www.example.com.".to_string(),
+            ]),
+            ("1.0.10.in-addr.arpa.".to_string(), vec![
+                "1.0.10.in-addr.arpa. IN NS ns1.example.com.".to_string(),
+                "7 300 IN PTR db.example.com.".to_string(),
+            ]),
+        ]);
+    }
+
+    #[test]
+    fn wildcards_are_not_reversed() {
+        let zones = reverse(ZONE);
+        assert!(zones.iter().all(|(origin, _)| origin != "0.128.10.in-addr.arpa."));
+        assert!(zones.iter().flat_map(|(_, records)| records).all(|r| !r.contains('*')));
+    }
+
+    #[test]
+    fn reverse_zones_of_several_zones_are_merged() {
+        let other = ZONE.replace("example.com.", "example.org.").replace(" 1 7200", " 9 7200");
+        let mut manifests = vec![];
+        for zone in [ZONE, &other] {
+            let (manifest, _) = Manifest::parse(zone, ParseMode::Strict).expect("manifest should parse");
+            manifests.extend(reverse_manifests(&manifest));
+        }
+        let merged = merge_reverse_manifests(manifests);
+        assert_eq!(merged.len(), 3);
+        // the first zone provides the SOA and NS records, with the latest serial.
+        assert!(merged.iter().all(|m| m.get_soa().get_serial() == 9));
+        let (origin, records) = &zones(merged)[1];
+        assert_eq!(origin, "0.0.10.in-addr.arpa.");
+        assert_eq!(records, &vec![
+            "0.0.10.in-addr.arpa. IN NS ns1.example.com.",
+            "1 IN PTR ns1.example.com.",
+            "5 IN PTR web.example.com.",
+            "5 IN PTR www.example.com.",
+            "1 IN PTR ns1.example.org.",
+            "5 IN PTR web.example.org.",
+            "5 IN PTR www.example.org.",
+        ]);
+    }
+
+    #[test]
+    fn serials_of_reverse_zones_move_forward_when_they_change() {
+        let derive = |zone: &str| {
+            let (manifest, _) = Manifest::parse(zone, ParseMode::Strict).expect("manifest should parse");
+            reverse_manifests(&manifest).remove(1)
+        };
+        let previous = derive(&ZONE.replace(" 1 7200", " 5 7200"));
+        let serial = |m: Manifest| m.get_soa().get_serial();
+
+        // the forward zone changed elsewhere: the reverse zone keeps its serial.
+        assert_eq!(serial(follow_serial(derive(&ZONE.replace(" 1 7200", " 6 7200")), &previous)), 5);
+        assert_eq!(serial(follow_serial(derive(ZONE), &previous)), 5);
+        // its content changed with a serial behind the last one, e.g. from another forward zone.
+        let changed = ZONE.replace("10.0.0.5", "10.0.0.6");
+        assert_eq!(serial(follow_serial(derive(&changed), &previous)), 6);
+        assert_eq!(serial(follow_serial(derive(&changed.replace(" 1 7200", " 8 7200")), &previous)), 8);
+    }
+}
@@ -0,0 +1,154 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use rocket::serde::{Deserialize, Serialize};
+
+// Serial numbers are compared in a window of half their space (RFC 1982 §3.2).
+const SERIAL_HALF: u32 = 1 << 31;
+
+const SECONDS_PER_DAY: u64 = 86400;
+
+// ----------------------------------------- SerialPolicy ------------------------------------------
+
+/// SerialPolicy tells how the serial of the SOA record moves forward when the zone changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub(crate) enum SerialPolicy {
+    // serial + 1.
+    #[default]
+    Counter,
+    // `YYYYMMDDnn`: the date of the change, followed by a two-digit revision of the day.
+    Date,
+    // seconds since the unix epoch.
+    UnixTime,
+}
+
+impl SerialPolicy {
+    /// returns the serial following `serial`, at time `now`. The result is always after `serial`
+    /// in serial number arithmetic, even if `serial` was set ahead of the policy by hand: the
+    /// serial is then incremented until the policy catches up.
+    pub(crate) fn next(&self, serial: u32, now: SystemTime) -> u32 {
+        let candidate = match self {
+            Self::Counter => None,
+            Self::Date => Some(date_serial(now)),
+            Self::UnixTime => Some(unix_seconds(now) as u32),
+        };
+        match candidate {
+            Some(candidate) if is_after(candidate, serial) => candidate,
+            _ => add(serial, 1),
+        }
+    }
+}
+
+/// tells whether serial `a` comes after serial `b` (RFC 1982 §3.2). Serials exactly half the
+/// space apart are not comparable, and neither is after the other.
+pub(crate) fn is_after(a: u32, b: u32) -> bool {
+    let distance = a.wrapping_sub(b);
+    distance != 0 && distance < SERIAL_HALF
+}
+
+/// adds `n` to a serial, wrapping around `u32::MAX` (RFC 1982 §3.1).
+/// `n` should be less than 2^31 for the result to come after `serial`.
+pub(crate) fn add(serial: u32, n: u32) -> u32 {
+    serial.wrapping_add(n)
+}
+
+// date_serial returns the first serial of the day of `now`, i.e. `YYYYMMDD00` in UTC.
+fn date_serial(now: SystemTime) -> u32 {
+    let (year, month, day) = civil_from_days((unix_seconds(now) / SECONDS_PER_DAY) as i64);
+    (year as u32) * 1_000_000 + month * 10_000 + day * 100
+}
+
+fn unix_seconds(now: SystemTime) -> u64 {
+    now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// converts days since the unix epoch to a (year, month, day) date of the proleptic Gregorian
+/// calendar (http://howardhinnant.github.io/date_algorithms.html).
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// converts a date of the proleptic Gregorian calendar to days since the unix epoch; the inverse
+/// of `civil_from_days`.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use super::{add, civil_from_days, days_from_civil, is_after, SerialPolicy, SECONDS_PER_DAY, SERIAL_HALF};
+
+    // 2024-03-05, 12:00 UTC.
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(days_from_civil(2024, 3, 5) as u64 * SECONDS_PER_DAY + 43200)
+    }
+
+    #[test]
+    fn serials_wrap_around() {
+        assert!(is_after(1, 0));
+        assert!(!is_after(0, 1));
+        assert!(!is_after(7, 7));
+        assert!(is_after(0, u32::MAX));
+        assert!(is_after(10, u32::MAX - 10));
+        assert!(is_after(SERIAL_HALF - 1, 0));
+        // serials half the space apart are not comparable.
+        assert!(!is_after(SERIAL_HALF, 0));
+        assert!(!is_after(0, SERIAL_HALF));
+
+        assert_eq!(add(u32::MAX, 1), 0);
+        assert_eq!(add(u32::MAX - 1, 5), 3);
+        assert!(is_after(add(u32::MAX, SERIAL_HALF - 1), u32::MAX));
+    }
+
+    #[test]
+    fn counters_increment_and_wrap_around() {
+        assert_eq!(SerialPolicy::Counter.next(41, now()), 42);
+        assert_eq!(SerialPolicy::Counter.next(u32::MAX, now()), 0);
+    }
+
+    #[test]
+    fn dates_count_the_revisions_of_the_day() {
+        assert_eq!(civil_from_days(days_from_civil(2024, 3, 5)), (2024, 3, 5));
+        assert_eq!(SerialPolicy::Date.next(2024030400, now()), 2024030500);
+        assert_eq!(SerialPolicy::Date.next(1, now()), 2024030500);
+        assert_eq!(SerialPolicy::Date.next(2024030500, now()), 2024030501);
+        // the 100th revision of the day overflows on the next day, which catches up later.
+        assert_eq!(SerialPolicy::Date.next(2024030599, now()), 2024030600);
+        assert_eq!(SerialPolicy::Date.next(2024030600, now()), 2024030601);
+        let tomorrow = now() + Duration::from_secs(SECONDS_PER_DAY);
+        assert_eq!(SerialPolicy::Date.next(2024030601, tomorrow), 2024030602);
+        let next_week = now() + Duration::from_secs(7 * SECONDS_PER_DAY);
+        assert_eq!(SerialPolicy::Date.next(2024030602, next_week), 2024031200);
+    }
+
+    #[test]
+    fn unix_times_are_seconds_since_the_epoch() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(SerialPolicy::UnixTime.next(5, now), 1_700_000_000);
+        assert_eq!(SerialPolicy::UnixTime.next(1_700_000_000, now), 1_700_000_001);
+    }
+
+    #[test]
+    fn serials_set_ahead_by_hand_are_incremented_until_policies_catch_up() {
+        assert_eq!(SerialPolicy::Date.next(2030010100, now()), 2030010101);
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(SerialPolicy::UnixTime.next(1_800_000_000, now), 1_800_000_001);
+        // a serial more than half the space ahead is behind in serial arithmetic.
+        assert_eq!(SerialPolicy::UnixTime.next(add(1_700_000_000, SERIAL_HALF + 1), now), 1_700_000_000);
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::fs;
+use ed25519_dalek::Signer as _;
+use crate::dns_manifest_parser::encoding::from_base64;
+use crate::dns_manifest_parser::manifest::Manifest;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::record::wire::{canonical_labels, signed_data};
+use crate::dns_manifest_parser::record::{
+    type_code, type_name, DNSKEYRecord, NSECRecord, RRSIGRecord, RecordData,
+    DS, NS, NSEC, NSEC3, NSEC3PARAM, RRSIG,
+};
+
+// DNSSEC algorithm numbers of the supported keys (RFC 8624 §3.1).
+const ECDSAP256SHA256: u8 = 13;
+const ED25519: u8 = 15;
+
+// A single key signs the whole zone (Combined Signing Key): Zone Key and Secure Entry Point flags.
+const CSK_FLAGS: u16 = 257;
+
+// Signatures are valid from an hour in the past, to tolerate clocks of validators running late.
+const INCEPTION_OFFSET: u32 = 3600;
+
+// ----------------------------------------- SigningKey --------------------------------------------
+
+pub(crate) enum SigningKey {
+    EcdsaP256Sha256(p256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+impl SigningKey {
+    /// parses a private key in the format written by BIND's `dnssec-keygen`, e.g.
+    /// `Kexample.com.+013+12345.private`:
+    ///
+    ///     Private-key-format: v1.3
+    ///     Algorithm: 13 (ECDSAP256SHA256)
+    ///     PrivateKey: <base64>
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        let field = |name: &str| s
+            .lines()
+            .find_map(|l| l.trim().strip_prefix(name)?.strip_prefix(':').map(str::trim))
+            .ok_or_else(|| format!("private key should contain a `{}` field", name));
+
+        let algorithm = field("Algorithm")?;
+        let algorithm: u8 = algorithm
+            .split_whitespace()
+            .next()
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| format!("algorithm should be a number, received: {}", algorithm))?;
+        let private_key = from_base64(field("PrivateKey")?)?;
+        let private_key: [u8; 32] = private_key.as_slice().try_into().map_err(|_| format!(
+            "private key should be 32 bytes long, received: {}", private_key.len()
+        ))?;
+
+        match algorithm {
+            ECDSAP256SHA256 => p256::ecdsa::SigningKey::from_bytes(&private_key.into())
+                .map(Self::EcdsaP256Sha256)
+                .map_err(|e| e.to_string()),
+            ED25519 => Ok(Self::Ed25519(ed25519_dalek::SigningKey::from_bytes(&private_key))),
+            n => Err(format!(
+                "unsupported algorithm {}, expected {} (ECDSAP256SHA256) or {} (ED25519)", n, ECDSAP256SHA256, ED25519
+            )),
+        }
+    }
+
+    pub(crate) fn from_path(path: &str) -> Result<Self, String> {
+        let s = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Self::parse(&s).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// returns the DNSKEY record publishing this key at the apex of a zone.
+    pub(crate) fn dnskey(&self, apex: &DomainName, class: &str) -> DNSKEYRecord {
+        DNSKEYRecord::new(apex.clone(), class, CSK_FLAGS, self.algorithm(), &self.public_key())
+    }
+
+    fn algorithm(&self) -> u8 {
+        match self {
+            Self::EcdsaP256Sha256(_) => ECDSAP256SHA256,
+            Self::Ed25519(_) => ED25519,
+        }
+    }
+
+    // public_key returns the key in the format of DNSKEY records (RFC 6605 §4, RFC 8080 §3).
+    fn public_key(&self) -> Vec<u8> {
+        match self {
+            // the uncompressed point, without its leading 0x04 tag.
+            Self::EcdsaP256Sha256(key) => key.verifying_key().to_encoded_point(false).as_bytes()[1..].to_vec(),
+            Self::Ed25519(key) => key.verifying_key().to_bytes().to_vec(),
+        }
+    }
+
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::EcdsaP256Sha256(key) => {
+                let signature: p256::ecdsa::Signature = key.sign(data);
+                signature.to_bytes().to_vec()
+            }
+            Self::Ed25519(key) => key.sign(data).to_bytes().to_vec(),
+        }
+    }
+}
+
+// -------------------------------------------- sign -----------------------------------------------
+
+/// signs the zone of `manifest` (RFC 4035 §2): publishes the DNSKEY record of `key` at the apex,
+/// chains the names of the zone with NSEC records and signs every authoritative RRset.
+/// Signatures are valid for `validity` seconds from `now`, in seconds since the unix epoch.
+///
+/// Previous RRSIG, NSEC and NSEC3 records are replaced. Names below a delegation (glue) are kept
+/// unsigned and out of the chain, and only the DS and NSEC RRsets of delegations are signed.
+pub(crate) fn sign(manifest: &Manifest, key: &SigningKey, now: u32, validity: u32) -> Result<Manifest, String> {
+    let origin = manifest.get_origin();
+    let ttl = manifest.get_ttl().get_ttl();
+    let apex = DomainName::new_unchecked(&origin.get_origin()).resolve(origin);
+    let soa = manifest.get_soa().for_zone(&apex, origin);
+    let class = soa.get_class();
+    let dnskey = key.dnskey(&apex, &class);
+    let validity = (now.wrapping_sub(INCEPTION_OFFSET), now.wrapping_add(validity));
+
+    let mut records: Vec<RecordData> = manifest.get_records()
+        .iter()
+        .filter(|r| ![RRSIG, NSEC, NSEC3, NSEC3PARAM].contains(&r.get_type()))
+        .filter(|r| r.get_name().is_in_zone(origin))
+        .map(|r| r.resolve(origin))
+        .collect();
+    // other DNSKEY records are kept, e.g. to pre-publish the next key.
+    let published = records.iter().any(|r| match r {
+        RecordData::DNSKEY(_) => r.get_name() == &apex && r.get_rdata() == RecordData::DNSKEY(dnskey.clone()).get_rdata(),
+        _ => false,
+    });
+    if !published {
+        records.push(RecordData::DNSKEY(dnskey.clone()));
+    }
+    records.push(RecordData::SOA(soa.clone()));
+
+    // RRsets of the zone, by name in canonical order then by type.
+    let mut nodes: BTreeMap<Vec<Vec<u8>>, BTreeMap<u16, Vec<RecordData>>> = BTreeMap::new();
+    for record in records {
+        let mut labels = canonical_labels(record.get_name()).map_err(|e| e.to_string())?;
+        labels.reverse();
+        let code = type_code(record.get_type()).unwrap_or_default();
+        nodes.entry(labels).or_default().entry(code).or_default().push(record);
+    }
+
+    let mut apex_labels = canonical_labels(&apex).map_err(|e| e.to_string())?;
+    apex_labels.reverse();
+    let ns = type_code(NS).unwrap_or_default();
+    let delegations: Vec<&Vec<Vec<u8>>> = nodes
+        .iter()
+        .filter(|(labels, rrsets)| **labels != apex_labels && rrsets.contains_key(&ns))
+        .map(|(labels, _)| labels)
+        .collect();
+    let is_occluded = |labels: &Vec<Vec<u8>>| delegations
+        .iter()
+        .any(|d| labels.len() > d.len() && labels.starts_with(d));
+    let chain: Vec<&Vec<Vec<u8>>> = nodes.keys().filter(|labels| !is_occluded(labels)).collect();
+
+    // NSEC records are cached as long as negative answers (RFC 9077).
+    let nsec_ttl = soa.get_minimum().min(RecordData::SOA(soa.clone()).get_ttl().unwrap_or(ttl));
+    let sign_rrset = |rrset: &[RecordData]| -> Result<RecordData, String> {
+        let rrsig = RRSIGRecord::covering(rrset, ttl, &dnskey, validity).map_err(|e| e.to_string())?;
+        let data = signed_data(&rrsig, rrset, origin).map_err(|e| e.to_string())?;
+        Ok(RecordData::RRSIG(rrsig.with_signature(&key.sign(&data))))
+    };
+
+    let mut signed: Vec<RecordData> = vec![];
+    for (labels, rrsets) in nodes.iter() {
+        let is_delegation = delegations.contains(&labels);
+        let is_occluded = is_occluded(labels);
+        for rrset in rrsets.values() {
+            signed.extend(rrset.iter().filter(|r| !matches!(r, RecordData::SOA(_))).cloned());
+            if !is_occluded && (!is_delegation || rrset[0].get_type() == DS) {
+                signed.push(sign_rrset(rrset)?);
+            }
+        }
+
+        let Some(i) = chain.iter().position(|l| *l == labels) else { continue };
+        let next = owner(&nodes[chain[(i + 1) % chain.len()]]);
+        let mut types: Vec<u16> = rrsets.keys().copied().collect();
+        types.extend([RRSIG, NSEC].iter().filter_map(|t| type_code(t)));
+        types.sort_unstable();
+        let nsec = RecordData::NSEC(NSECRecord::new(
+            owner(rrsets),
+            Some(nsec_ttl),
+            &class,
+            // the next name is signed as is (RFC 6840 §5.1): write it in canonical form.
+            DomainName::new_unchecked(&next.as_str().to_ascii_lowercase()),
+            types.into_iter().map(type_name).collect(),
+        ));
+        let rrsig = sign_rrset(std::slice::from_ref(&nsec))?;
+        signed.extend([nsec, rrsig]);
+    }
+
+    Ok(Manifest::from_parts(origin.clone(), manifest.get_ttl().clone(), soa, signed))
+}
+
+// owner returns the name owning RRsets, as written in the manifest.
+fn owner(rrsets: &BTreeMap<u16, Vec<RecordData>>) -> DomainName {
+    rrsets.values().flatten().next().expect("nodes own at least one record").get_name().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::ecdsa::signature::Verifier;
+    use super::{sign, SigningKey};
+    use crate::dns_manifest_parser::encoding::{from_base64, to_base64};
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+    use crate::dns_manifest_parser::record::wire::signed_data;
+    use crate::dns_manifest_parser::record::{RecordData, DNSKEY, NSEC, RRSIG};
+
+    // `sub` is delegated, `ns.sub` is its glue.
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 7 7200 3600 1209600 300
+@ IN NS ns1
+ns1 IN A 192.0.2.1
+www IN A 192.0.2.2
+sub IN NS ns.sub
+sub IN DS 12345 13 2 2bb183af5f22588179a53b0a98631fad1a292118ae2e5bd5f17ab87a4eeabb9d
+ns.sub IN A 192.0.2.3
+";
+    const NOW: u32 = 1_700_000_000;
+
+    fn key(algorithm: &str) -> SigningKey {
+        SigningKey::parse(&format!(
+            "Private-key-format: v1.3\nAlgorithm: {}\nPrivateKey: {}\n", algorithm, to_base64(&[7; 32])
+        )).expect("key should parse")
+    }
+
+    fn signed(key: &SigningKey) -> Manifest {
+        let (manifest, _) = Manifest::parse(ZONE, ParseMode::Strict).expect("manifest should parse");
+        sign(&manifest, key, NOW, 3600).expect("zone should be signed")
+    }
+
+    // field returns the `i`th field of the rdata of `record`.
+    fn field(record: &RecordData, i: usize) -> String {
+        record.get_rdata().split_whitespace().nth(i).unwrap_or_default().to_string()
+    }
+
+    // base64 returns the base64 field ending the rdata of `record`, from its `i`th field.
+    fn base64(record: &RecordData, i: usize) -> Vec<u8> {
+        from_base64(&record.get_rdata().split_whitespace().skip(i).collect::<String>()).unwrap()
+    }
+
+    fn verify(key: &SigningKey, public_key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+        match key {
+            SigningKey::EcdsaP256Sha256(_) => {
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&[&[4], public_key].concat()).unwrap();
+                let signature = p256::ecdsa::Signature::from_slice(signature).unwrap();
+                key.verify(data, &signature).is_ok()
+            }
+            SigningKey::Ed25519(_) => {
+                let key = ed25519_dalek::VerifyingKey::from_bytes(public_key.try_into().unwrap()).unwrap();
+                let signature = ed25519_dalek::Signature::from_slice(signature).unwrap();
+                key.verify(data, &signature).is_ok()
+            }
+        }
+    }
+
+    fn assert_signed(algorithm: &str) {
+        let key = key(algorithm);
+        let manifest = signed(&key);
+        let origin = manifest.get_origin();
+        let records = manifest.get_records();
+
+        let dnskey = records.iter().find(|r| r.get_type() == DNSKEY).expect("DNSKEY should be published");
+        assert_eq!(dnskey.get_name().as_str(), "example.com.");
+        assert_eq!(field(dnskey, 2), algorithm);
+        let public_key = base64(dnskey, 3);
+
+        let mut covered: Vec<(String, String)> = Vec::new();
+        for record in records.iter().filter(|r| r.get_type() == RRSIG) {
+            let RecordData::RRSIG(rrsig) = record else { unreachable!() };
+            let type_covered = field(record, 0);
+            let rrset: Vec<RecordData> = match type_covered.as_str() {
+                "SOA" => vec![RecordData::SOA(manifest.get_soa().clone())],
+                t => records
+                    .iter()
+                    .filter(|r| r.get_type() == t && r.get_name() == record.get_name())
+                    .cloned()
+                    .collect(),
+            };
+            let data = signed_data(rrsig, &rrset, origin).unwrap();
+            let signature = base64(record, 8);
+            assert!(verify(&key, &public_key, &data, &signature), "{} should be verified", record);
+            covered.push((record.get_name().to_string(), type_covered));
+        }
+        covered.sort();
+        let expected = [
+            ("example.com.", "DNSKEY"), ("example.com.", "NS"), ("example.com.", "NSEC"), ("example.com.", "SOA"),
+            ("ns1.example.com.", "A"), ("ns1.example.com.", "NSEC"),
+            // only the DS and NSEC RRsets of delegations are signed, and glue is not.
+            ("sub.example.com.", "DS"), ("sub.example.com.", "NSEC"),
+            ("www.example.com.", "A"), ("www.example.com.", "NSEC"),
+        ];
+        assert_eq!(covered, expected.map(|(n, t)| (n.to_string(), t.to_string())));
+
+        // the chain follows the canonical order, leaves glue out and loops back to the apex.
+        let chain: Vec<String> = records
+            .iter()
+            .filter(|r| r.get_type() == NSEC)
+            .map(|r| format!("{} {}", r.get_name(), r.get_rdata()))
+            .collect();
+        assert_eq!(chain, [
+            "example.com. ns1.example.com. NS SOA RRSIG NSEC DNSKEY",
+            "ns1.example.com. sub.example.com. A RRSIG NSEC",
+            "sub.example.com. www.example.com. NS DS RRSIG NSEC",
+            "www.example.com. example.com. A RRSIG NSEC",
+        ]);
+        // NSEC records are cached as long as negative answers.
+        assert!(records.iter().filter(|r| r.get_type() == NSEC).all(|r| r.get_ttl() == Some(300)));
+    }
+
+    #[test]
+    fn zones_are_signed_with_ecdsa_keys() {
+        assert_signed("13");
+    }
+
+    #[test]
+    fn zones_are_signed_with_ed25519_keys() {
+        assert_signed("15");
+    }
+
+    #[test]
+    fn previous_signatures_are_replaced() {
+        let key = key("13");
+        let resigned = sign(&signed(&key), &key, NOW + 60, 3600).unwrap();
+        let count = |m: &Manifest, t: &str| m.get_records().iter().filter(|r| r.get_type() == t).count();
+        assert_eq!(count(&resigned, RRSIG), count(&signed(&key), RRSIG));
+        assert_eq!(count(&resigned, NSEC), 4);
+        assert_eq!(count(&resigned, DNSKEY), 1);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::dns_manifest_parser::error::{ParseError, ParseErrorKind};
+
+// ------------------------------------------- Token -----------------------------------------------
+
+#[derive(Clone, Debug)]
+pub(crate) struct Token {
+    pub(crate) text: String,
+    // 1-based position of the first character of the token.
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl Token {
+    pub(crate) fn new(text: &str, line: usize, column: usize) -> Self {
+        Self{ text: text.to_string(), line, column }
+    }
+}
+
+// ------------------------------------------- Entry -----------------------------------------------
+
+// An entry is a directive or a resource record, i.e. a logical line of a master file.
+// Parentheses let an entry span several physical lines.
+// Quoted character-strings keep their quotes and escapes, so that the record parsers can tell
+// them apart from unquoted ones.
+#[derive(Clone, Debug)]
+pub(crate) struct Entry {
+    pub(crate) tokens: Vec<Token>,
+    // the entry starts with a blank: the owner name of the previous record is reused.
+    pub(crate) owner_omitted: bool,
+    // 1-based physical lines on which the entry starts and ends.
+    pub(crate) line: usize,
+    pub(crate) end_line: usize,
+}
+
+impl Entry {
+    fn new(line: usize) -> Self {
+        Self{ tokens: vec![], owner_omitted: false, line, end_line: line }
+    }
+}
+
+// ------------------------------------------ tokenize ---------------------------------------------
+
+/// tokenize splits a master file into entries.
+/// It strips `;` comments and blank lines, joins parenthesized multi-line entries and keeps
+/// quoted character-strings (which may contain blanks, `;` and parentheses) as single tokens.
+/// Entries which cannot be split, e.g. with an unterminated quote, are returned as errors, and
+/// tokenizing resumes on the next line.
+pub(crate) fn tokenize(s: &str) -> (Vec<Entry>, Vec<ParseError>) {
+    let (mut entries, mut errors) = (Vec::new(), Vec::new());
+    let mut line = 1;
+    // an unbalanced parenthesis is only found at the end of the file: the entry opening it is
+    // dropped, and the lines following its first one are read again.
+    while let Some(next_line) = scan(s, line, &mut entries, &mut errors) {
+        line = next_line;
+    }
+    (entries, errors)
+}
+
+// scan tokenizes `s` from the line `first_line`. It returns the line to scan from again when the
+// end of the file is reached within parentheses.
+fn scan(s: &str, first_line: usize, entries: &mut Vec<Entry>, errors: &mut Vec<ParseError>) -> Option<usize> {
+    let mut entry = Entry::new(first_line);
+    let mut token: Option<Token> = None;
+    let mut depth: usize = 0;
+    let mut quoted = false;
+    let mut comment = false;
+    // the current entry is broken: the rest of its line is skipped and the entry dropped.
+    let mut broken = false;
+    let (mut line, mut column) = (first_line, 0);
+    let mut line_start = true;
+
+    let offset = match first_line {
+        1 => 0,
+        n => s.match_indices('\n').nth(n - 2).map_or(s.len(), |(i, _)| i + 1),
+    };
+    let mut chars = s[offset..].chars().peekable();
+    while let Some(c) = chars.next() {
+        column += 1;
+
+        if c == '\n' {
+            if quoted && !broken {
+                errors.push(syntax_error(&token, "unterminated quoted string"));
+                broken = true;
+            }
+            if broken {
+                entry = Entry::new(line + 1);
+                token = None;
+                (depth, quoted, broken) = (0, false, false);
+            }
+            flush_token(&mut entry, &mut token);
+            if depth == 0 {
+                flush_entry(entries, &mut entry, line + 1);
+            }
+            line += 1;
+            column = 0;
+            comment = false;
+            line_start = true;
+            continue
+        }
+        if comment || broken {
+            continue
+        }
+        if line_start && depth == 0 && entry.tokens.is_empty() {
+            entry.line = line;
+            entry.owner_omitted = c == ' ' || c == '\t';
+        }
+        line_start = false;
+
+        match c {
+            '\\' => {
+                let t = token.get_or_insert_with(|| Token::new("", line, column));
+                t.text.push(c);
+                if chars.peek() == Some(&'\n') {
+                    let message = "escaped newline is not supported".to_string();
+                    errors.push(ParseError::new(ParseErrorKind::Syntax, &t.text, message).at_token(t));
+                    broken = true;
+                } else if let Some(escaped) = chars.next() {
+                    column += 1;
+                    t.text.push(escaped);
+                }
+            }
+            '"' => {
+                token.get_or_insert_with(|| Token::new("", line, column)).text.push(c);
+                quoted = !quoted;
+            }
+            _ if quoted => {
+                token.get_or_insert_with(|| Token::new("", line, column)).text.push(c);
+            }
+            ';' => {
+                flush_token(&mut entry, &mut token);
+                comment = true;
+            }
+            '(' => {
+                flush_token(&mut entry, &mut token);
+                depth += 1;
+            }
+            ')' => {
+                flush_token(&mut entry, &mut token);
+                if depth == 0 {
+                    errors.push(ParseError::new(ParseErrorKind::Syntax, ")", "unbalanced parenthesis".to_string())
+                        .at(line, column));
+                    broken = true;
+                    continue
+                }
+                depth -= 1;
+            }
+            ' ' | '\t' | '\r' => flush_token(&mut entry, &mut token),
+            _ => {
+                token.get_or_insert_with(|| Token::new("", line, column)).text.push(c);
+            }
+        }
+        entry.end_line = line;
+    }
+
+    if quoted && !broken {
+        errors.push(syntax_error(&token, "unterminated quoted string"));
+        return None
+    }
+    if broken {
+        return None
+    }
+    if depth > 0 {
+        errors.push(ParseError::new(
+            ParseErrorKind::Syntax,
+            "(",
+            "unbalanced parenthesis, reached end of file".to_string()
+        ).at(entry.line, 1));
+        return Some(entry.line + 1)
+    }
+    flush_token(&mut entry, &mut token);
+    flush_entry(entries, &mut entry, line + 1);
+    None
+}
+
+fn flush_token(entry: &mut Entry, token: &mut Option<Token>) {
+    if let Some(t) = token.take() {
+        entry.tokens.push(t);
+    }
+}
+
+fn flush_entry(entries: &mut Vec<Entry>, entry: &mut Entry, next_line: usize) {
+    let e = std::mem::replace(entry, Entry::new(next_line));
+    if !e.tokens.is_empty() {
+        entries.push(e);
+    }
+}
+
+fn syntax_error(token: &Option<Token>, message: &str) -> ParseError {
+    match token {
+        Some(t) => ParseError::new(ParseErrorKind::Syntax, &t.text, message.to_string()).at_token(t),
+        None => ParseError::new(ParseErrorKind::Syntax, "", message.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tokenize;
+
+    #[test]
+    fn entries_span_parentheses_and_skip_comments() {
+        let (entries, errors) = tokenize("; header\n@ IN SOA ns1 admin ( 1 ; serial\n  2 3 4 5 )\n\n  IN TXT \"a ; (b)\"\n");
+        assert!(errors.is_empty());
+        let texts: Vec<Vec<&str>> = entries.iter().map(|e| e.tokens.iter().map(|t| t.text.as_str()).collect()).collect();
+        assert_eq!(texts, vec![
+            vec!["@", "IN", "SOA", "ns1", "admin", "1", "2", "3", "4", "5"],
+            vec!["IN", "TXT", "\"a ; (b)\""],
+        ]);
+        assert_eq!((entries[0].line, entries[0].end_line, entries[0].owner_omitted), (2, 3, false));
+        assert_eq!((entries[1].line, entries[1].end_line, entries[1].owner_omitted), (5, 5, true));
+        let serial = &entries[0].tokens[5];
+        assert_eq!((serial.line, serial.column), (2, 22));
+        assert_eq!((entries[0].tokens[6].line, entries[0].tokens[6].column), (3, 3));
+    }
+
+    #[test]
+    fn errors_are_located_at_their_line_and_column() {
+        let (entries, errors) = tokenize("a IN A 10.0.0.1\nb IN TXT \"open\nc IN A 10.0.0.2 )\nd IN A 10.0.0.3\n");
+        let names: Vec<&str> = entries.iter().map(|e| e.tokens[0].text.as_str()).collect();
+        assert_eq!(names, ["a", "d"]);
+        let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].starts_with("line 2, column 10: unterminated quoted string"), "{}", errors[0]);
+        assert!(errors[1].starts_with("line 3, column 17: unbalanced parenthesis"), "{}", errors[1]);
+    }
+}
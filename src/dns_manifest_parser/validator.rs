@@ -0,0 +1,307 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use rocket::serde::Serialize;
+use crate::dns_manifest_parser::lookup;
+use crate::dns_manifest_parser::manifest::Manifest;
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::record::RecordData;
+
+// ------------------------------------------- Severity --------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) enum Severity {
+    // The zone would be refused or misbehave once served.
+    Error,
+    // The zone can be served, but is likely not what was intended.
+    Warning,
+}
+
+// -------------------------------------------- Check ----------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) enum Check {
+    // retry < refresh and expire > refresh + retry.
+    SoaTimers,
+    // NS targets inside the zone need address records, i.e. glue for delegations.
+    NameServer,
+    // a CNAME cannot coexist with other data but its DNSSEC records, nor be at the apex
+    // (RFC 1034 §3.6.2, RFC 4035 §2.5).
+    CnameExclusivity,
+    Duplicate,
+    // records whose owner name is not in the zone.
+    OutOfZone,
+    // targets inside the zone without any record, nor wildcard covering them.
+    DanglingTarget,
+}
+
+// ------------------------------------------ Diagnostic -------------------------------------------
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Diagnostic {
+    severity: Severity,
+    check: Check,
+    // offending record, in zone format.
+    record: String,
+    message: String,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, check: Check, record: &dyn Display, message: String) -> Self {
+        Self{ severity, check, record: record.to_string(), message }
+    }
+
+    pub(crate) fn get_severity(&self) -> Severity { self.severity }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} ({:?}): {} (`{}`)", self.severity, self.check, self.message, self.record)
+    }
+}
+
+// ------------------------------------------- validate --------------------------------------------
+
+/// validate lints a manifest, returning one diagnostic per problem found.
+/// Names outside of the zone cannot be checked and are assumed to exist.
+pub(crate) fn validate(manifest: &Manifest) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    check_soa_timers(manifest, &mut diagnostics);
+    check_out_of_zone(manifest, &mut diagnostics);
+    check_cname_exclusivity(manifest, &mut diagnostics);
+    check_duplicates(manifest, &mut diagnostics);
+    check_targets(manifest, &mut diagnostics);
+    diagnostics
+}
+
+/// returns the errors of `manifest` which `previous` does not have, i.e. those added by a change.
+pub(crate) fn added_errors(previous: &Manifest, manifest: &Manifest) -> Vec<Diagnostic> {
+    let previous: HashSet<String> = validate(previous).iter().map(|d| d.to_string()).collect();
+    validate(manifest)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error && !previous.contains(&d.to_string()))
+        .collect()
+}
+
+fn check_soa_timers(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+    let soa = manifest.get_soa();
+    let (refresh, retry, expire) = (soa.get_refresh(), soa.get_retry(), soa.get_expire());
+    if retry >= refresh {
+        diagnostics.push(Diagnostic::new(Severity::Error, Check::SoaTimers, soa, format!(
+            "retry ({}) should be less than refresh ({})", retry, refresh
+        )))
+    }
+    let sum = u64::from(refresh) + u64::from(retry);
+    if u64::from(expire) <= sum {
+        diagnostics.push(Diagnostic::new(Severity::Error, Check::SoaTimers, soa, format!(
+            "expire ({}) should be greater than refresh + retry ({})", expire, sum
+        )))
+    }
+}
+
+fn check_out_of_zone(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+    let origin = manifest.get_origin();
+    for r in manifest.get_records().iter().filter(|r| !r.get_name().is_in_zone(origin)) {
+        diagnostics.push(Diagnostic::new(Severity::Error, Check::OutOfZone, r, format!(
+            "`{}` is not in zone `{}`", r.get_name().resolve(origin), origin.get_origin()
+        )))
+    }
+}
+
+fn check_cname_exclusivity(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+    let origin = manifest.get_origin();
+    let apex = manifest.get_soa().get_name().to_canonical(origin);
+    let nodes = nodes(manifest);
+
+    // names are checked once, at their first CNAME record.
+    let mut checked: HashSet<String> = HashSet::new();
+    for r in manifest.get_records().iter().filter(|r| r.get_type() == record::CNAME) {
+        let name = r.get_name().to_canonical(origin);
+        if !checked.insert(name.clone()) {
+            continue
+        }
+        if name == apex {
+            diagnostics.push(Diagnostic::new(Severity::Error, Check::CnameExclusivity, r,
+                "CNAME should not be at the apex of the zone".to_string()
+            ))
+        } else if let Some(other) = nodes[&name]
+            .iter()
+            .find(|&&t| ![record::CNAME, record::RRSIG, record::NSEC].contains(&t))
+        {
+            diagnostics.push(Diagnostic::new(Severity::Error, Check::CnameExclusivity, r, format!(
+                "CNAME should not coexist with other data, found {} record", other
+            )))
+        }
+        let count = manifest.get_records()
+            .iter()
+            .filter(|o| o.get_type() == record::CNAME && o.get_name().to_canonical(origin) == name)
+            .count();
+        if count > 1 {
+            diagnostics.push(Diagnostic::new(Severity::Error, Check::CnameExclusivity, r, format!(
+                "name should hold a single CNAME record, found {}", count
+            )))
+        }
+    }
+}
+
+fn check_duplicates(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+    let origin = manifest.get_origin();
+    let mut seen: HashSet<(String, &str, String)> = HashSet::new();
+    for r in manifest.get_records() {
+        let key = (r.get_name().to_canonical(origin), r.get_type(), r.get_rdata());
+        if !seen.insert(key) {
+            diagnostics.push(Diagnostic::new(Severity::Warning, Check::Duplicate, r,
+                "record is defined more than once".to_string()
+            ))
+        }
+    }
+}
+
+// check_targets checks that the targets inside the zone exist; NS targets also need an address.
+fn check_targets(manifest: &Manifest, diagnostics: &mut Vec<Diagnostic>) {
+    let origin = manifest.get_origin();
+    let nodes = nodes(manifest);
+    let names = lookup::existing_names(nodes.keys().cloned(), &origin.get_origin().to_ascii_lowercase());
+
+    for r in manifest.get_records() {
+        let target = match r.get_target() {
+            Some(target) if target.is_in_zone(origin) => target.to_canonical(origin),
+            _ => continue,
+        };
+        // targets missing from the zone may be covered by a wildcard.
+        let types = match names.contains(&target) {
+            true => nodes.get(&target),
+            false => lookup::source_of_synthesis(&target, &names).and_then(|w| nodes.get(&w)),
+        };
+        let has_address = types.is_some_and(|v| v.contains(&record::A) || v.contains(&record::AAAA));
+
+        match r {
+            RecordData::NS(_) if !has_address => {
+                diagnostics.push(Diagnostic::new(Severity::Error, Check::NameServer, r, format!(
+                    "name server `{}` is inside the zone and should have an A or AAAA record", target
+                )))
+            }
+            _ if types.is_none() => {
+                diagnostics.push(Diagnostic::new(Severity::Warning, Check::DanglingTarget, r, format!(
+                    "target `{}` is inside the zone but has no record", target
+                )))
+            }
+            _ => {}
+        }
+    }
+}
+
+// nodes maps the canonical names of the zone to the types of their records.
+fn nodes(manifest: &Manifest) -> HashMap<String, Vec<&str>> {
+    let origin = manifest.get_origin();
+    let mut nodes: HashMap<String, Vec<&str>> = HashMap::new();
+    nodes.entry(manifest.get_soa().get_name().to_canonical(origin)).or_default().push(record::SOA);
+    for r in manifest.get_records() {
+        nodes.entry(r.get_name().to_canonical(origin)).or_default().push(r.get_type());
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{added_errors, validate, Check, Severity};
+    use crate::dns_manifest_parser::manifest::{Manifest, ManifestBuilder, ParseMode};
+
+    const ZONE: &str = "$ORIGIN example.com.
+$TTL 3600
+@ IN SOA ns1 admin 1 7200 3600 1209600 3600
+@ IN NS ns1
+ns1 IN A 10.0.0.1
+web IN A 10.0.0.5
+*.dev IN A 10.0.0.6
+";
+
+    fn manifest(records: &str) -> Manifest {
+        Manifest::parse(&format!("{}{}", ZONE, records), ParseMode::Strict).unwrap().0
+    }
+
+    // checks returns the check, severity and record of each diagnostic.
+    fn checks(records: &str) -> Vec<(Check, Severity, String)> {
+        validate(&manifest(records))
+            .into_iter()
+            .map(|d| (d.check, d.severity, d.record))
+            .collect()
+    }
+
+    #[test]
+    fn valid_zones_have_no_diagnostic() {
+        assert_eq!(checks("www IN CNAME web\nmail IN MX 10 x.dev\n"), []);
+    }
+
+    #[test]
+    fn soa_timers_are_checked() {
+        let zone = ZONE.replace("1 7200 3600 1209600", "1 3600 7200 9000");
+        let (manifest, _) = Manifest::parse(&zone, ParseMode::Strict).unwrap();
+        let messages: Vec<String> = validate(&manifest).iter().map(|d| d.message.to_string()).collect();
+        assert_eq!(messages, [
+            "retry (7200) should be less than refresh (3600)",
+            "expire (9000) should be greater than refresh + retry (10800)",
+        ]);
+    }
+
+    #[test]
+    fn names_out_of_zone_are_errors() {
+        assert_eq!(checks("yolo.com. IN A 10.0.0.7\n"), [
+            (Check::OutOfZone, Severity::Error, "yolo.com. IN A 10.0.0.7".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn cnames_are_exclusive_once_per_name() {
+        assert_eq!(checks("@ IN CNAME web\n"), [
+            (Check::CnameExclusivity, Severity::Error, "@ IN CNAME web".to_string()),
+        ]);
+        let diagnostics = validate(&manifest("www IN CNAME web\nwww IN CNAME ns1\nwww IN TXT \"a\"\n"));
+        let messages: Vec<String> = diagnostics.iter().map(|d| d.message.to_string()).collect();
+        assert_eq!(messages, [
+            "CNAME should not coexist with other data, found TXT record",
+            "name should hold a single CNAME record, found 2",
+        ]);
+        assert!(diagnostics.iter().all(|d| d.record == "www IN CNAME web"));
+    }
+
+    #[test]
+    fn duplicates_are_warnings() {
+        assert_eq!(checks("web IN A 10.0.0.5\n"), [
+            (Check::Duplicate, Severity::Warning, "web IN A 10.0.0.5".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn name_servers_inside_the_zone_need_an_address() {
+        assert_eq!(checks("sub IN NS ns.sub\nother IN NS ns.example.org.\n"), [
+            (Check::NameServer, Severity::Error, "sub IN NS ns.sub".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn targets_inside_the_zone_should_exist() {
+        assert_eq!(checks("www IN CNAME missing\nftp IN CNAME pr-42.dev\next IN CNAME example.org.\n"), [
+            (Check::DanglingTarget, Severity::Warning, "www IN CNAME missing".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn changes_fail_on_the_errors_they_add_only() {
+        let previous = manifest("yolo.com. IN A 10.0.0.7\n");
+        assert!(added_errors(&previous, &previous).is_empty());
+        let errors = added_errors(&previous, &manifest("yolo.com. IN A 10.0.0.7\nyolo.org. IN A 10.0.0.8\n"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].record, "yolo.org. IN A 10.0.0.8");
+
+        let builder = &mut ManifestBuilder::from(previous);
+        let record = manifest("www.yolo.org. IN A 10.0.0.9\n").get_records()[4].clone();
+        let e = builder.add_record(record).unwrap().check().err();
+        assert!(e.is_some_and(|e| e.contains("`www.yolo.org.` is not in zone `example.com.`")));
+        let record = manifest("www IN A 10.0.0.9\n").get_records()[4].clone();
+        assert!(ManifestBuilder::from(manifest("yolo.com. IN A 10.0.0.7\n")).add_record(record).unwrap().check().is_ok());
+    }
+}
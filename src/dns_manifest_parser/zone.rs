@@ -0,0 +1,156 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use crate::dns_manifest_parser::manifest::Manifest;
+
+const DNS_MANIFEST_PATH: &str = "./dns_manifest";
+const DNSSEC_KEY_PATH: &str = "./dnssec.private";
+const SIGNED_MANIFEST_PATH: &str = "./dns_manifest.signed";
+const HISTORY_DIR: &str = "./history";
+
+// Other zones are kept in directories, one file per zone named after its origin.
+pub(crate) const ZONES_DIR: &str = "./zones";
+const KEYS_DIR: &str = "./keys";
+const SIGNED_DIR: &str = "./signed";
+
+/// Zone locates the files of a zone served by betterdns: its manifest, history, DNSSEC key and
+/// signed zone. The default zone is `./dns_manifest`; the others are `./zones/<origin>`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Zone {
+    // lowercase origin of a zone of `ZONES_DIR`, without trailing dot; `None` for the default zone.
+    origin: Option<String>,
+}
+
+impl Zone {
+    /// returns the zone of `ZONES_DIR` for `origin`, written with or without trailing dot.
+    pub(crate) fn new(origin: &str) -> Result<Self, String> {
+        let name = origin.trim_end_matches('.').to_ascii_lowercase();
+        // the origin names files: letters, digits, `-` and `_` only.
+        let is_hostname = !name.is_empty() && name.split('.').all(|label| {
+            !label.is_empty() && label.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        });
+        match is_hostname {
+            true => Ok(Self{ origin: Some(name) }),
+            false => Err(format!("zone should be a domain name, received: {}", origin)),
+        }
+    }
+
+    /// lists the zones: the default zone if its manifest exists, then the zones of `ZONES_DIR`
+    /// sorted by origin.
+    pub(crate) fn list() -> Result<Vec<Self>, String> {
+        let mut zones: Vec<Self> = vec![];
+        if Path::new(DNS_MANIFEST_PATH).exists() {
+            zones.push(Self::default())
+        }
+        let entries = match fs::read_dir(ZONES_DIR) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(zones),
+            Err(e) => return Err(e.to_string()),
+        };
+        let mut origins: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .filter_map(|e| e.file_name().to_str().map(str::to_string))
+            // temporary files are hidden.
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        origins.sort();
+        zones.extend(origins.iter().filter_map(|origin| Self::new(origin).ok()));
+        Ok(zones)
+    }
+
+    /// returns the origin of a zone of `ZONES_DIR`, e.g. `example.org`.
+    pub(crate) fn get_origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    pub(crate) fn manifest_path(&self) -> String {
+        self.path(DNS_MANIFEST_PATH, ZONES_DIR, "")
+    }
+
+    /// returns the path the manifest is written to before being renamed over `manifest_path`.
+    pub(crate) fn tmp_path(&self) -> String {
+        match &self.origin {
+            None => format!("{}.tmp", DNS_MANIFEST_PATH),
+            Some(origin) => format!("{}/.{}.tmp", ZONES_DIR, origin),
+        }
+    }
+
+    /// returns the directory holding a snapshot of each version of the manifest, by serial.
+    pub(crate) fn history_dir(&self) -> String {
+        self.path(HISTORY_DIR, HISTORY_DIR, "")
+    }
+
+    /// returns the path of the private key the zone is signed with, if it exists.
+    pub(crate) fn key_path(&self) -> String {
+        self.path(DNSSEC_KEY_PATH, KEYS_DIR, ".private")
+    }
+
+    pub(crate) fn signed_path(&self) -> String {
+        self.path(SIGNED_MANIFEST_PATH, SIGNED_DIR, "")
+    }
+
+    /// returns the path the signed zone is written to before being renamed over `signed_path`.
+    pub(crate) fn signed_tmp_path(&self) -> String {
+        match &self.origin {
+            None => format!("{}.tmp", SIGNED_MANIFEST_PATH),
+            Some(origin) => format!("{}/.{}.tmp", SIGNED_DIR, origin),
+        }
+    }
+
+    /// checks that a manifest may be written to this zone, i.e. that they have the same origin.
+    pub(crate) fn check_origin(&self, manifest: &Manifest) -> Result<(), String> {
+        let found = manifest.get_origin().get_origin();
+        match &self.origin {
+            Some(origin) if !found.trim_end_matches('.').eq_ignore_ascii_case(origin) => Err(format!(
+                "origin of the manifest should be {}., received: {}", origin, found
+            )),
+            _ => Ok(())
+        }
+    }
+
+    // path returns `default` for the default zone, `<dir>/<origin><extension>` otherwise.
+    fn path(&self, default: &str, dir: &str, extension: &str) -> String {
+        match &self.origin {
+            None => default.to_string(),
+            Some(origin) => format!("{}/{}{}", dir, origin, extension),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zone;
+    use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+
+    #[test]
+    fn zones_are_named_after_their_origin() {
+        let zone = Zone::new("Example.ORG.").unwrap();
+        assert_eq!(zone.get_origin(), Some("example.org"));
+        assert_eq!(zone.manifest_path(), "./zones/example.org");
+        assert_eq!(zone.tmp_path(), "./zones/.example.org.tmp");
+        assert_eq!(zone.history_dir(), "./history/example.org");
+        assert_eq!(zone.key_path(), "./keys/example.org.private");
+        assert_eq!(zone.signed_path(), "./signed/example.org");
+
+        let default = Zone::default();
+        assert_eq!(default.get_origin(), None);
+        assert_eq!(default.manifest_path(), "./dns_manifest");
+        assert_eq!(default.history_dir(), "./history");
+
+        for origin in ["", ".", "a..b", "../etc", "a/b", "a b"] {
+            assert!(Zone::new(origin).is_err(), "{}", origin);
+        }
+    }
+
+    #[test]
+    fn manifests_are_written_to_the_zone_of_their_origin() {
+        let (manifest, _) = Manifest::parse(
+            "$ORIGIN example.org.\n$TTL 3600\n@ IN SOA ns1 admin 1 2 3 4 5\n", ParseMode::Strict
+        ).unwrap();
+        assert!(Zone::new("EXAMPLE.org").unwrap().check_origin(&manifest).is_ok());
+        assert!(Zone::default().check_origin(&manifest).is_ok());
+        let e = Zone::new("example.com").unwrap().check_origin(&manifest).unwrap_err();
+        assert_eq!(e, "origin of the manifest should be example.com., received: example.org.");
+    }
+}
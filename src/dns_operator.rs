@@ -1,6 +1,10 @@
+mod reverse_zones;
+mod signed_zone;
 mod watcher_file;
+mod zones;
 pub(crate) mod operator;
 pub(crate) mod runtime;
 pub(crate) mod watcher;
 pub(crate) mod controller;
+pub(crate) mod corefile;
 
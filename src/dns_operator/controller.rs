@@ -1,5 +1,8 @@
+use crate::dns_operator::reverse_zones::sync_reverse_zones;
 use crate::dns_operator::runtime::Runtime;
+use crate::dns_operator::signed_zone::sync_signed_zones;
 use crate::dns_operator::watcher::{Event, Watcher};
+use crate::dns_operator::zones::sync_zones;
 
 // ------------------------------------------ Controller -------------------------------------------
 
@@ -13,6 +16,39 @@ impl Controller {
     pub(crate) fn new() -> Self { Self::Null }
     pub(crate) fn new_default() -> Self { Self::Default(DefaultController::new()) }
 
+    // Declares the zones of the zones directory in the Corefile on every reconciliation.
+    pub(crate) fn with_zones(self) -> Self {
+        match self {
+            Controller::Null => Controller::Null,
+            Controller::Default(mut controller) => {
+                controller.zones = true;
+                Controller::Default(controller)
+            }
+        }
+    }
+
+    // Derives and maintains reverse zones from the dns manifests on every reconciliation.
+    pub(crate) fn with_reverse_zones(self) -> Self {
+        match self {
+            Controller::Null => Controller::Null,
+            Controller::Default(mut controller) => {
+                controller.reverse_zones = true;
+                Controller::Default(controller)
+            }
+        }
+    }
+
+    // Signs the dns manifests on every reconciliation, and again before their signatures expire.
+    pub(crate) fn with_signing(self) -> Self {
+        match self {
+            Controller::Null => Controller::Null,
+            Controller::Default(mut controller) => {
+                controller.signing = true;
+                Controller::Default(controller)
+            }
+        }
+    }
+
     pub(crate) fn reconcile(
         &self,
         event: Event,
@@ -29,24 +65,64 @@ impl Controller {
 }
 // ---------------------------------------- DefaultRuntime -----------------------------------------
 
-pub(crate) struct DefaultController {}
+pub(crate) struct DefaultController {
+    // zones enables serving the zones of `./zones`, one per file named after its origin.
+    zones: bool,
+    // reverse_zones enables the generation of `in-addr.arpa.` & `ip6.arpa.` zones.
+    reverse_zones: bool,
+    // signing enables DNSSEC signing of the zones, e.g. with the key at `./dnssec.private`.
+    signing: bool,
+}
 
 impl DefaultController {
-    pub(crate) fn new() -> Self {Self{}}
+    pub(crate) fn new() -> Self {Self{ zones: false, reverse_zones: false, signing: false }}
 
     fn reconcile(
         &self,
         event: Event,
         runtime: &mut Runtime,
-        _watcher: &mut Watcher
+        watcher: &mut Watcher
     ) -> Result<(), String> {
         match event {
-            Event::Create => unimplemented!(),
+            Event::Create => {
+                self.sync(watcher, true)?;
+                runtime.run()
+            },
             Event::Update => {
+                self.sync(watcher, false)?;
                 runtime.shutdown()?;
                 runtime.run()
             },
             Event::Delete => unimplemented!()
         }
     }
-}
\ No newline at end of file
+
+    // sync generates the files derived from the dns manifests before (re)starting the runtime.
+    // Errors stop the operator at startup only: afterwards they are logged and the files they
+    // concern are left as they are, until the next change.
+    fn sync(&self, watcher: &mut Watcher, startup: bool) -> Result<(), String> {
+        let check = |result: Result<(), String>| match result {
+            Err(e) if !startup => {
+                println!("sync failed: {}", e);
+                Ok(())
+            }
+            result => result,
+        };
+        // zones are signed first, so that only the zones signed successfully are served signed.
+        let mut signed = Vec::new();
+        if self.signing {
+            check(sync_signed_zones().map(|(zones, deadline)| {
+                // wake up to re-sign the zones even if their dns manifests do not change.
+                watcher.set_deadline(deadline);
+                signed = zones;
+            }))?;
+        }
+        if self.zones {
+            check(sync_zones(&signed))?;
+        }
+        if self.reverse_zones {
+            check(sync_reverse_zones())?;
+        }
+        Ok(())
+    }
+}
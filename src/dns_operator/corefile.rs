@@ -0,0 +1,730 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::ops::Range;
+use std::path::Path;
+
+pub(crate) const COREFILE_PATH: &str = "Corefile";
+const DNS_PORT: u16 = 53;
+
+// Sections of the Corefile owned by betterdns, e.g. `# BEGIN betterdns zones`: their server blocks
+// are regenerated on every reconciliation.
+const BEGIN_MARKER: &str = "# BEGIN betterdns";
+const END_MARKER: &str = "# END betterdns";
+
+const INDENT: &str = "    ";
+
+// ------------------------------------------- Corefile --------------------------------------------
+
+/// Corefile is the configuration of CoreDNS: server blocks serving zones on a port, each with the
+/// plugins handling their queries, e.g.
+///
+///     example.org:53 {
+///         log
+///         forward . 8.8.8.8 {
+///             except internal.example.org
+///         }
+///     }
+///
+/// The text between server blocks, and the text of the blocks and directives betterdns does not
+/// change, is kept as read: comments, blank lines, snippets, imports and anything it cannot parse
+/// are rendered back as is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Corefile {
+    // text around the blocks: `gaps[i]` precedes `blocks[i]`, the last one ends the Corefile.
+    gaps: Vec<String>,
+    blocks: Vec<ServerBlock>,
+}
+
+impl Corefile {
+    pub(crate) fn parse(s: &str) -> Self {
+        let mut parser = Parser{ s, tokens: tokenize(s), position: 0 };
+        let mut corefile = Self::default();
+        let mut gap_start = 0;
+        while let Some(token) = parser.peek() {
+            // comments, blank lines and stray braces stay in the gap.
+            if token.kind != Kind::Word {
+                parser.position += 1;
+                continue
+            }
+            let position = parser.position;
+            match parser.server_block() {
+                Ok((block, span)) => {
+                    corefile.gaps.push(s[gap_start..span.start].to_string());
+                    corefile.blocks.push(block);
+                    gap_start = span.end;
+                }
+                Err(Unparsed::Line) => {
+                    parser.position = position;
+                    parser.skip_line();
+                }
+                Err(Unparsed::Rest) => break,
+            }
+        }
+        corefile.gaps.push(s[gap_start..].to_string());
+        corefile
+    }
+
+    /// reads the Corefile at `path`; a missing Corefile is empty, as betterdns writes it.
+    pub(crate) fn from_path(path: &str) -> Result<Self, String> {
+        Ok(Self::parse(&read(path)?))
+    }
+
+    pub(crate) fn server_blocks_mut(&mut self) -> impl Iterator<Item = &mut ServerBlock> {
+        self.blocks.iter_mut()
+    }
+}
+
+impl Display for Corefile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (i, gap) in self.gaps.iter().enumerate() {
+            write!(f, "{}", gap)?;
+            if let Some(block) = self.blocks.get(i) {
+                write!(f, "{}", block)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// ------------------------------------------ ServerBlock ------------------------------------------
+
+/// ServerBlock serves its zones with the plugins of its directives.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ServerBlock {
+    keys: Vec<ServerKey>,
+    // text from the first key to the opening brace, as read.
+    header: Option<String>,
+    // text around the directives: `gaps[i]` precedes `directives[i]`, the last one the closing brace.
+    gaps: Vec<String>,
+    directives: Vec<Directive>,
+}
+
+impl ServerBlock {
+    pub(crate) fn new(keys: Vec<ServerKey>) -> Self {
+        Self{ keys, header: None, gaps: vec!["\n".to_string()], directives: vec![] }
+    }
+
+    /// serves `zone` on the DNS port from the zone file at `path`.
+    pub(crate) fn zone_file(zone: &str, path: &str) -> Self {
+        Self::new(vec![ServerKey::new(zone, DNS_PORT)]).with_plugin(Directive::new("file", vec![path.to_string()]))
+    }
+
+    pub(crate) fn with_plugin(mut self, directive: Directive) -> Self {
+        self.set_plugin(directive);
+        self
+    }
+
+    /// returns whether the block serves `zone`, written with or without trailing dot.
+    pub(crate) fn serves(&self, zone: &str) -> bool {
+        self.keys.iter().any(|k| k.zone.trim_end_matches('.').eq_ignore_ascii_case(zone.trim_end_matches('.')))
+    }
+
+    pub(crate) fn get_plugin(&self, name: &str) -> Option<&Directive> {
+        self.directives.iter().find(|d| d.name == name)
+    }
+
+    /// enables a plugin, replacing its previous directive if any; its trailing comment is kept.
+    pub(crate) fn set_plugin(&mut self, directive: Directive) -> &mut Self {
+        match self.directives.iter_mut().find(|d| d.name == directive.name) {
+            Some(d) if d.args == directive.args && d.block == directive.block => {}
+            Some(d) => *d = Directive{ comment: d.comment.take(), ..directive },
+            None => self.push(directive),
+        }
+        self
+    }
+
+    /// serves the zone file `to` in place of `from`, keeping the other arguments and the settings of
+    /// the `file` plugin; a `file` plugin serving another zone file is left as is.
+    pub(crate) fn replace_zone_file(&mut self, from: &str, to: &str) -> &mut Self {
+        for directive in self.directives.iter_mut().filter(|d| d.name == "file") {
+            match directive.args.first_mut() {
+                Some(arg) if arg.trim_start_matches("./") == from && arg != to => *arg = to.to_string(),
+                _ => continue,
+            }
+            directive.source = None;
+        }
+        self
+    }
+
+    pub(crate) fn remove_plugin(&mut self, name: &str) -> &mut Self {
+        while let Some(i) = self.directives.iter().position(|d| d.name == name) {
+            // the line of the directive goes with it.
+            let after = self.gaps.remove(i + 1);
+            let before = &mut self.gaps[i];
+            if let Some(end) = before.rfind('\n') {
+                before.truncate(end);
+            }
+            before.push_str(&after);
+            self.directives.remove(i);
+        }
+        self
+    }
+
+    // push adds a directive on its own line, after the others.
+    fn push(&mut self, directive: Directive) {
+        // a block written on a single line, e.g. `. { whoami }`, is spread over several lines.
+        if !self.gaps.iter().any(|g| g.contains('\n')) {
+            self.gaps = vec![format!("\n{}", INDENT); self.directives.len()];
+            self.gaps.push("\n".to_string());
+        }
+        let indent = match self.directives.len() {
+            0 => INDENT,
+            n => self.gaps[n - 1].rsplit('\n').next().filter(|s| s.trim().is_empty()).unwrap_or(INDENT),
+        };
+        self.gaps.insert(self.directives.len(), format!("\n{}", indent));
+        self.directives.push(directive);
+    }
+
+    // keep_plugins adds the plugins of `previous` this block does not configure itself.
+    fn keep_plugins(&mut self, previous: &ServerBlock) {
+        for directive in previous.directives.iter() {
+            if self.get_plugin(&directive.name).is_none() {
+                self.push(directive.clone());
+            }
+        }
+    }
+}
+
+impl Display for ServerBlock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.header {
+            Some(header) => write!(f, "{}", header)?,
+            None => {
+                let keys: Vec<String> = self.keys.iter().map(|k| k.to_string()).collect();
+                write!(f, "{} {{", keys.join(" "))?;
+            }
+        }
+        for (i, gap) in self.gaps.iter().enumerate() {
+            write!(f, "{}", gap)?;
+            if let Some(directive) = self.directives.get(i) {
+                write!(f, "{}", directive)?;
+            }
+        }
+        write!(f, "}}")
+    }
+}
+
+// ------------------------------------------- ServerKey -------------------------------------------
+
+/// ServerKey is an address a server block listens to, e.g. `example.org:53` or `tls://.:853`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ServerKey {
+    // transport, e.g. `dns`, `tls` or `grpc`.
+    scheme: Option<String>,
+    // zone, e.g. `example.org.`, `.` or `10.0.0.0/24`.
+    zone: String,
+    // port, e.g. `53` or an environment variable, e.g. `{$PORT}`.
+    port: Option<String>,
+}
+
+impl ServerKey {
+    fn new(zone: &str, port: u16) -> Self {
+        Self{ scheme: None, zone: zone.to_string(), port: Some(port.to_string()) }
+    }
+
+    fn parse(s: &str) -> Self {
+        let (scheme, address) = match s.split_once("://") {
+            Some((scheme, address)) => (Some(scheme.to_string()), address),
+            None => (None, s),
+        };
+        let (zone, port) = match address.rsplit_once(':') {
+            Some((zone, port)) => (zone, Some(port.to_string())),
+            None => (address, None),
+        };
+        Self{ scheme, zone: zone.to_string(), port }
+    }
+}
+
+impl Display for ServerKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{}://", scheme)?;
+        }
+        write!(f, "{}", self.zone)?;
+        match &self.port {
+            Some(port) => write!(f, ":{}", port),
+            None => Ok(()),
+        }
+    }
+}
+
+// ------------------------------------------- Directive -------------------------------------------
+
+/// Directive configures a plugin, e.g. `forward . 8.8.8.8`, with the settings of its block if any,
+/// e.g. `except internal.example.org`.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Directive {
+    name: String,
+    args: Vec<String>,
+    // block of settings, from its opening to its closing brace, as read.
+    block: Option<String>,
+    // trailing comment, with its leading `#`.
+    comment: Option<String>,
+    // text of the directive as read, rendered while it is unchanged.
+    source: Option<String>,
+}
+
+impl Directive {
+    pub(crate) fn new(name: &str, args: Vec<String>) -> Self {
+        Self{ name: name.to_string(), args, block: None, comment: None, source: None }
+    }
+}
+
+impl Display for Directive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(source) = &self.source {
+            return write!(f, "{}", source)
+        }
+        write!(f, "{}", self.name)?;
+        for arg in self.args.iter() {
+            write!(f, " {}", quote(arg))?;
+        }
+        if let Some(block) = &self.block {
+            write!(f, " {}", block)?;
+        }
+        match &self.comment {
+            Some(comment) => write!(f, " {}", comment),
+            None => Ok(()),
+        }
+    }
+}
+
+// --------------------------------------------- Parser --------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Word,
+    Open,
+    Close,
+    Comment,
+    Newline,
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: Kind,
+    // quotes removed.
+    text: String,
+    // span of the token in the Corefile.
+    start: usize,
+    end: usize,
+}
+
+impl Token {
+    fn new(kind: Kind, text: &str, start: usize, end: usize) -> Self {
+        Self{ kind, text: text.to_string(), start, end }
+    }
+}
+
+// Unparsed is text the parser leaves as written: up to the end of its line, e.g. `import common`,
+// or up to the end of the Corefile, e.g. a block which is never closed.
+enum Unparsed {
+    Line,
+    Rest,
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(token) = self.next() {
+            if token.kind == Kind::Newline {
+                return
+            }
+        }
+    }
+
+    // server_block parses a block from its keys to its closing brace, returning its span.
+    fn server_block(&mut self) -> Result<(ServerBlock, Range<usize>), Unparsed> {
+        let mut words: Vec<Token> = vec![];
+        let open = loop {
+            match self.next() {
+                Some(token) if token.kind == Kind::Word => words.push(token),
+                Some(token) if token.kind == Kind::Open => break token,
+                _ => return Err(Unparsed::Line),
+            }
+        };
+        let start = words.first().map_or(open.start, |w| w.start);
+        let (gaps, directives, end) = self.body(open.end)?;
+        let keys = words
+            .iter()
+            .flat_map(|w| w.text.split(','))
+            .filter(|k| !k.is_empty())
+            .map(ServerKey::parse)
+            .collect();
+        let header = Some(self.s[start..open.end].to_string());
+        Ok((ServerBlock{ keys, header, gaps, directives }, start..end))
+    }
+
+    // body parses the directives of a block opened at `gap_start` up to its closing brace, returning
+    // the end of the block.
+    fn body(&mut self, mut gap_start: usize) -> Result<(Vec<String>, Vec<Directive>, usize), Unparsed> {
+        let mut gaps: Vec<String> = vec![];
+        let mut directives: Vec<Directive> = vec![];
+        loop {
+            let Some(token) = self.next() else { return Err(Unparsed::Rest) };
+            match token.kind {
+                Kind::Newline | Kind::Comment => continue,
+                Kind::Open => return Err(Unparsed::Rest),
+                Kind::Close => {
+                    gaps.push(self.s[gap_start..token.start].to_string());
+                    return Ok((gaps, directives, token.end))
+                }
+                Kind::Word => {
+                    let (directive, end) = self.directive(&token)?;
+                    gaps.push(self.s[gap_start..token.start].to_string());
+                    directives.push(directive);
+                    gap_start = end;
+                }
+            }
+        }
+    }
+
+    // directive parses the arguments, block and trailing comment of the directive `name`.
+    fn directive(&mut self, name: &Token) -> Result<(Directive, usize), Unparsed> {
+        let mut args: Vec<String> = vec![];
+        let mut block = None;
+        let mut end = name.end;
+        while let Some(token) = self.peek().cloned() {
+            match token.kind {
+                Kind::Word => {
+                    self.position += 1;
+                    args.push(token.text);
+                    end = token.end;
+                }
+                Kind::Open => {
+                    self.position += 1;
+                    end = self.skip_block()?;
+                    block = Some(self.s[token.start..end].to_string());
+                    break
+                }
+                _ => break,
+            }
+        }
+        let mut comment = None;
+        if let Some(token) = self.peek().filter(|t| t.kind == Kind::Comment).cloned() {
+            self.position += 1;
+            end = token.end;
+            comment = Some(token.text);
+        }
+        let source = Some(self.s[name.start..end].to_string());
+        Ok((Directive{ name: name.text.clone(), args, block, comment, source }, end))
+    }
+
+    // skip_block skips a block up to its closing brace, returning its end.
+    fn skip_block(&mut self) -> Result<usize, Unparsed> {
+        let mut depth = 1;
+        while let Some(token) = self.next() {
+            match token.kind {
+                Kind::Open => depth += 1,
+                Kind::Close if depth == 1 => return Ok(token.end),
+                Kind::Close => depth -= 1,
+                _ => {}
+            }
+        }
+        Err(Unparsed::Rest)
+    }
+}
+
+// tokenize splits a Corefile into tokens; an unterminated quote ends the tokens.
+// Braces glued to a word are tokens of their own, e.g. `example.org{`, unless they belong to a
+// placeholder, e.g. `{$PORT}`.
+fn tokenize(s: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '\n' => tokens.push(Token::new(Kind::Newline, "\n", start, start + 1)),
+            c if c.is_whitespace() => continue,
+            '#' => {
+                let line = &s[start..s[start..].find('\n').map_or(s.len(), |i| start + i)];
+                let comment = line.trim_end();
+                while chars.next_if(|&(i, _)| i < start + line.len()).is_some() {}
+                tokens.push(Token::new(Kind::Comment, comment, start, start + comment.len()));
+            }
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some((end, '"')) => {
+                            tokens.push(Token::new(Kind::Word, &text, start, end + 1));
+                            break
+                        }
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, c)) => text.push(c),
+                            None => return tokens,
+                        },
+                        Some((_, c)) => text.push(c),
+                        None => return tokens,
+                    }
+                }
+            }
+            _ => {
+                let end = s[start..].find(char::is_whitespace).map_or(s.len(), |i| start + i);
+                while chars.next_if(|&(i, _)| i < end).is_some() {}
+                let word = &s[start..end];
+                let (opens, closes) = (word.matches('{').count(), word.matches('}').count());
+                match word {
+                    "{" => tokens.push(Token::new(Kind::Open, word, start, end)),
+                    "}" => tokens.push(Token::new(Kind::Close, word, start, end)),
+                    _ if word.ends_with('{') && opens > closes => {
+                        tokens.push(Token::new(Kind::Word, &word[..word.len() - 1], start, end - 1));
+                        tokens.push(Token::new(Kind::Open, "{", end - 1, end));
+                    }
+                    _ if word.ends_with('}') && closes > opens => {
+                        tokens.push(Token::new(Kind::Word, &word[..word.len() - 1], start, end - 1));
+                        tokens.push(Token::new(Kind::Close, "}", end - 1, end));
+                    }
+                    _ => tokens.push(Token::new(Kind::Word, word, start, end)),
+                }
+            }
+        }
+    }
+    tokens
+}
+
+// -------------------------------------------- utils ----------------------------------------------
+
+// quote quotes arguments which would not be read back as a single token.
+fn quote(arg: &str) -> String {
+    let (opens, closes) = (arg.matches('{').count(), arg.matches('}').count());
+    let needs_quotes = arg.is_empty()
+        || arg.starts_with('#')
+        || (arg.ends_with('{') && opens >= closes)
+        || (arg.ends_with('}') && closes >= opens)
+        || arg.contains(|c: char| c.is_whitespace() || c == '"');
+    match needs_quotes {
+        true => format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\"")),
+        false => arg.to_string(),
+    }
+}
+
+// replace_section replaces the server blocks of the section `name` owned by betterdns, appending the
+// section at the end of the Corefile if it does not exist yet. The rest of the Corefile is kept as is.
+// Plugins enabled by hand on a block of the section are kept when it is regenerated.
+// A section missing its END marker is an error: where it ends, and what was written by hand
+// after it, cannot be told.
+fn replace_section(corefile: &str, name: &str, blocks: Vec<ServerBlock>) -> Result<String, String> {
+    let begin_marker = format!("{} {}", BEGIN_MARKER, name);
+    let end_marker = format!("{} {}", END_MARKER, name);
+    let line = |marker: &str, from: usize| -> Option<Range<usize>> {
+        let mut start = from;
+        for line in corefile[from..].split_inclusive('\n') {
+            if line.trim() == marker {
+                return Some(start..start + line.len())
+            }
+            start += line.len();
+        }
+        None
+    };
+    let begin = line(&begin_marker, 0);
+    let end = begin.as_ref().and_then(|begin| line(&end_marker, begin.end));
+    if begin.is_some() && end.is_none() {
+        return Err(format!("`{}` should be followed by `{}`", begin_marker, end_marker))
+    }
+
+    let previous = match (&begin, &end) {
+        (Some(begin), Some(end)) => Corefile::parse(&corefile[begin.end..end.start]),
+        _ => Corefile::default(),
+    };
+    let section: String = blocks
+        .into_iter()
+        .map(|mut block| {
+            if let Some(kept) = previous.blocks.iter().find(|p| p.keys == block.keys) {
+                block.keep_plugins(kept);
+            }
+            format!("{}\n", block)
+        })
+        .collect();
+
+    match (begin, end) {
+        (Some(begin), Some(end)) => Ok(format!("{}{}{}", &corefile[..begin.end], section, &corefile[end.start..])),
+        _ => {
+            let separator = match corefile {
+                "" => "",
+                s if s.ends_with("\n\n") => "",
+                s if s.ends_with('\n') => "\n",
+                _ => "\n\n",
+            };
+            Ok(format!("{}{}{}\n{}{}\n", corefile, separator, begin_marker, section, end_marker))
+        }
+    }
+}
+
+// read reads the Corefile at `path`; a missing Corefile is empty.
+fn read(path: &str) -> Result<String, String> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(s),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(format!("{}: {}", path, e)),
+    }
+}
+
+// ---------------------------------------- Managed files ------------------------------------------
+
+// Declares `blocks` in the section `name` of the Corefile, leaving the rest of it untouched.
+pub(crate) fn sync_managed_section(name: &str, blocks: Vec<ServerBlock>) -> Result<(), String> {
+    let corefile = read(COREFILE_PATH)?;
+    let corefile = replace_section(&corefile, name, blocks).map_err(|e| format!("{}: {}", COREFILE_PATH, e))?;
+    write_if_changed(COREFILE_PATH, &corefile)
+}
+
+pub(crate) fn write_if_changed(path: &str, content: &str) -> Result<(), String> {
+    if Path::new(path).exists() && fs::read_to_string(path).map_err(|e| e.to_string())? == content {
+        return Ok(())
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replace_section, Corefile, Directive, ServerBlock};
+
+    const COREFILE: &str = "# served by hand
+(common) {
+  errors
+    log   # every query
+}
+
+example.org{
+\timport common
+\tforward . \"8.8.8.8\" {
+\t\texcept internal.example.org
+\t}
+}
+.:{$PORT} { whoami }
+import extra.conf
+
+# BEGIN betterdns zones
+example.com.:53 {
+    file zones/example.com
+    log
+}
+# END betterdns zones
+";
+
+    fn parse_blocks(corefile: &str) -> Vec<ServerBlock> {
+        Corefile::parse(corefile).blocks
+    }
+
+    #[test]
+    fn corefiles_are_rendered_as_read() {
+        let unclosed = "example.org {\n    log\n\n.:53 {\n    whoami\n}\n";
+        let unterminated = ".:53 {\n    forward . \"8.8.8.8\n}\n";
+        let stray = "}\nimport a\n{ log }\n. {\n  log }";
+        for s in [COREFILE, unclosed, unterminated, stray, "", "\n\n"] {
+            assert_eq!(Corefile::parse(s).to_string(), s);
+        }
+    }
+
+    #[test]
+    fn server_blocks_are_parsed() {
+        let blocks = parse_blocks(COREFILE);
+        assert_eq!(blocks.len(), 4);
+        assert!(blocks[1].serves("example.org."));
+        assert!(blocks[1].get_plugin("import").is_some());
+        assert_eq!(blocks[1].get_plugin("forward").unwrap().args, vec![".", "8.8.8.8"]);
+        assert!(blocks[2].serves("."));
+        assert_eq!(blocks[2].keys[0].port.as_deref(), Some("{$PORT}"));
+        assert!(blocks[2].get_plugin("whoami").is_some());
+        assert!(blocks[3].get_plugin("log").is_some());
+
+        // blocks after a block which is never closed are not parsed.
+        assert!(parse_blocks("example.org {\n    log\n\n.:53 {\n    whoami\n}\n").is_empty());
+    }
+
+    #[test]
+    fn only_changed_blocks_are_rendered() {
+        let mut corefile = Corefile::parse(COREFILE);
+        for block in corefile.server_blocks_mut().filter(|b| b.serves("example.org")) {
+            block.set_plugin(Directive::new("cache", vec![]));
+            block.set_plugin(Directive::new("forward", vec![".".to_string(), "1.1.1.1".to_string()]));
+        }
+        for block in corefile.server_blocks_mut().filter(|b| b.serves(".")) {
+            block.set_plugin(Directive::new("log", vec![]));
+        }
+        for block in corefile.server_blocks_mut().filter(|b| b.serves("(common)")) {
+            block.remove_plugin("errors");
+        }
+        let expected = COREFILE
+            .replace("  errors\n    log", "    log")
+            .replace("\tforward . \"8.8.8.8\" {\n\t\texcept internal.example.org\n\t}\n", "\tforward . 1.1.1.1\n\tcache\n")
+            .replace(".:{$PORT} { whoami }", ".:{$PORT} {\n    whoami\n    log\n}");
+        assert_eq!(corefile.to_string(), expected);
+
+        // enabling a plugin as it is configured keeps its text.
+        let mut corefile = Corefile::parse(COREFILE);
+        for block in corefile.server_blocks_mut() {
+            if block.serves("example.org") {
+                block.set_plugin(Directive::new("import", vec!["common".to_string()]));
+            }
+            block.remove_plugin("cache");
+        }
+        assert_eq!(corefile.to_string(), COREFILE);
+    }
+
+    #[test]
+    fn zone_files_are_replaced() {
+        let s = "example.org {\n    file ./dns_manifest example.org { reload 30s } # signed?\n    log\n}\n";
+        let mut corefile = Corefile::parse(s);
+        for block in corefile.server_blocks_mut() {
+            block.replace_zone_file("dns_manifest", "dns_manifest.signed");
+        }
+        let signed = s.replace("./dns_manifest", "dns_manifest.signed");
+        assert_eq!(corefile.to_string(), signed);
+
+        for block in corefile.server_blocks_mut() {
+            block.replace_zone_file("zones/example.org", "dns_manifest");
+        }
+        assert_eq!(corefile.to_string(), signed);
+    }
+
+    #[test]
+    fn managed_sections_are_replaced() {
+        let zones = vec![
+            ServerBlock::zone_file("example.com.", "zones/example.com"),
+            ServerBlock::zone_file("example.net.", "zones/example.net"),
+        ];
+        let replaced = replace_section(COREFILE, "zones", zones.clone()).unwrap();
+        let expected = COREFILE.replace(
+            "    log\n}\n# END",
+            "    log\n}\nexample.net.:53 {\n    file zones/example.net\n}\n# END",
+        );
+        assert_eq!(replaced, expected);
+        assert_eq!(replace_section(&replaced, "zones", zones).unwrap(), expected);
+
+        let reverse = vec![ServerBlock::zone_file("0.0.10.in-addr.arpa.", "reverse_zones/0.0.10.in-addr.arpa")];
+        let appended = replace_section(COREFILE, "reverse zones", reverse).unwrap();
+        assert_eq!(appended, format!(
+            "{}\n# BEGIN betterdns reverse zones\n0.0.10.in-addr.arpa.:53 {{\n    file reverse_zones/0.0.10.in-addr.arpa\n}}\n# END betterdns reverse zones\n",
+            COREFILE,
+        ));
+        assert_eq!(replace_section("", "zones", vec![]).unwrap(), "# BEGIN betterdns zones\n# END betterdns zones\n");
+    }
+
+    #[test]
+    fn sections_missing_their_end_are_not_replaced() {
+        let orphaned = COREFILE.replace("# END betterdns zones\n", "");
+        let e = replace_section(&orphaned, "zones", vec![]).err();
+        assert_eq!(e.as_deref(), Some("`# BEGIN betterdns zones` should be followed by `# END betterdns zones`"));
+        // an END marker before its BEGIN one does not end the section either.
+        let misplaced = format!("# END betterdns zones\n{}", orphaned);
+        assert!(replace_section(&misplaced, "zones", vec![]).is_err());
+    }
+}
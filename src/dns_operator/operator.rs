@@ -1,6 +1,6 @@
 use crate::dns_operator::controller::Controller;
 use crate::dns_operator::runtime::Runtime;
-use crate::dns_operator::watcher::Watcher;
+use crate::dns_operator::watcher::{Event, Watcher};
 
 // ------------------------------------------- Builder ---------------------------------------------
 
@@ -48,18 +48,23 @@ impl Operator {
        }
     }
 
+    // run returns the errors of the first reconciliation only: later ones are logged, and the
+    // operator keeps watching for the change fixing them.
     pub(crate) fn run(&mut self) -> Result<(), String> {
-        self.runtime.run()?;
+        self.controller.reconcile(Event::Create, &mut self.runtime, &mut self.watcher)?;
         loop {
             match self.watcher.watch() {
                 Ok(event) => {
-                    self.controller.reconcile(event, &mut self.runtime, &mut self.watcher)?;
+                    if let Err(e) = self.controller.reconcile(event, &mut self.runtime, &mut self.watcher) {
+                        println!("reconciliation failed: {}", e);
+                    }
                 }
                 Err(e) => return Err(e)
             }
         }
     }
 
+    #[allow(dead_code)]
     pub(crate) fn shutdown(&mut self) -> Result<(), String> {
         self.runtime.shutdown()
     }
@@ -0,0 +1,68 @@
+use std::collections::HashSet;
+use std::fs;
+use crate::dns_manifest_parser::error::join_errors;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::reverse::{follow_serial, merge_reverse_manifests, reverse_manifests};
+use crate::dns_manifest_parser::zone::Zone;
+use crate::dns_operator::corefile::{sync_managed_section, write_if_changed, ServerBlock};
+
+const REVERSE_ZONES_DIR: &str = "reverse_zones";
+const SECTION: &str = "reverse zones";
+
+// Derives the reverse zones of the dns manifests of every zone which can be parsed, writes one zone file per reverse
+// zone and declares them in the Corefile. Stale reverse zones are removed so forward and reverse
+// zones never drift.
+// Files are only written when their content changes, to avoid triggering the watcher needlessly.
+pub(crate) fn sync_reverse_zones() -> Result<(), String> {
+    let mut zones: Vec<Manifest> = Vec::new();
+    for zone in Zone::list()? {
+        let path = zone.manifest_path();
+        // a broken zone loses its reverse zones, but does not keep the others from being served.
+        let (manifest, diagnostics) = match Manifest::from_path(&path, ParseMode::Lenient) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                println!("{}: reverse zones not derived: {}", path, join_errors(&e));
+                continue
+            }
+        };
+        diagnostics.iter().for_each(|d| println!("{}: {}", path, d));
+        zones.extend(reverse_manifests(&manifest));
+    }
+    let zones = merge_reverse_manifests(zones);
+
+    fs::create_dir_all(REVERSE_ZONES_DIR).map_err(|e| e.to_string())?;
+    let mut blocks: Vec<ServerBlock> = Vec::new();
+    let mut zone_files: HashSet<String> = HashSet::new();
+    for zone in zones {
+        let origin = zone.get_origin().get_origin();
+        let zone_file = format!("{}/{}", REVERSE_ZONES_DIR, origin.trim_end_matches('.'));
+        let zone = match Manifest::from_path(&zone_file, ParseMode::Lenient) {
+            Ok((previous, _)) => follow_serial(zone, &previous),
+            Err(_) => zone,
+        };
+        // a reverse zone which cannot be written is left out, the others are still served.
+        if let Err(e) = write_if_changed(&zone_file, &zone.to_string()) {
+            println!("{}: not served: {}", zone_file, e);
+            continue
+        }
+
+        blocks.push(ServerBlock::zone_file(&origin, &zone_file));
+        zone_files.insert(zone_file);
+    }
+    remove_stale_zone_files(&zone_files)?;
+
+    sync_managed_section(SECTION, blocks)
+}
+
+fn remove_stale_zone_files(zone_files: &HashSet<String>) -> Result<(), String> {
+    let entries = fs::read_dir(REVERSE_ZONES_DIR).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let path = format!("{}/{}", REVERSE_ZONES_DIR, entry.file_name().to_string_lossy());
+        if !zone_files.contains(&path) {
+            if let Err(e) = fs::remove_file(&path) {
+                println!("{}: not removed: {}", path, e);
+            }
+        }
+    }
+    Ok(())
+}
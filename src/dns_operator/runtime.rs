@@ -22,7 +22,7 @@ impl Runtime {
     pub(crate) fn shutdown(&mut self) -> Result<(), String> {
         match self {
             Runtime::Null => unimplemented!(),
-            Runtime::CoreDNS(ref mut runtime) => return runtime.shutdown()
+            Runtime::CoreDNS(ref mut runtime) => runtime.shutdown()
         }
     }
 }
@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::dns_manifest_parser::error::join_errors;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::signer::{sign, SigningKey};
+use crate::dns_manifest_parser::zone::Zone;
+
+// Signatures are valid for two weeks and refreshed after one, leaving a week to fix a failing
+// operator before validators reject the zone.
+const SIGNATURE_VALIDITY: Duration = Duration::from_secs(14 * 24 * 3600);
+const RESIGN_INTERVAL: Duration = Duration::from_secs(7 * 24 * 3600);
+
+// Signs the dns manifest of every zone having a key, e.g. `./dnssec.private` for the default zone
+// or `./keys/<origin>.private`, and writes its signed zone, the zone file CoreDNS serves.
+// Returns the zones signed, the only ones to serve signed, and when they must be signed again,
+// before their signatures expire; `None` when there is no key, i.e. every zone is served unsigned.
+pub(crate) fn sync_signed_zones() -> Result<(Vec<Zone>, Option<Instant>), String> {
+    let mut signed = Vec::new();
+    let mut deadline = None;
+    for zone in Zone::list()? {
+        // a zone which cannot be signed is served unsigned, until a change lets it be signed.
+        match sync_signed_zone(&zone) {
+            Ok(Some(next)) => {
+                deadline = Some(deadline.map_or(next, |d: Instant| d.min(next)));
+                signed.push(zone);
+            }
+            Ok(None) => {}
+            Err(e) => println!("{}: not signed: {}", zone.manifest_path(), e),
+        }
+    }
+    Ok((signed, deadline))
+}
+
+fn sync_signed_zone(zone: &Zone) -> Result<Option<Instant>, String> {
+    let key_path = zone.key_path();
+    if !Path::new(&key_path).exists() {
+        println!("{} not found: {} is not signed", key_path, zone.manifest_path());
+        return Ok(None)
+    }
+    let key = SigningKey::from_path(&key_path)?;
+    let (manifest, _) = Manifest::from_path(&zone.manifest_path(), ParseMode::Lenient)
+        .map_err(|e| join_errors(&e))?;
+
+    // timestamps of signatures wrap around every 136 years (RFC 4034 §3.1.5).
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|e| e.to_string())?.as_secs() as u32;
+    let signed = sign(&manifest, &key, now, SIGNATURE_VALIDITY.as_secs() as u32)?;
+    let signed_path = zone.signed_path();
+    if let Some(dir) = Path::new(&signed_path).parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // CoreDNS never reads a partially written zone.
+    let tmp = zone.signed_tmp_path();
+    fs::write(&tmp, signed.to_string()).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, &signed_path).map_err(|e| e.to_string())?;
+    println!("{}: signed serial {}", signed_path, manifest.get_soa().get_serial());
+
+    Ok(Some(Instant::now() + RESIGN_INTERVAL))
+}
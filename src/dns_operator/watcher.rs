@@ -1,5 +1,7 @@
+use std::time::Instant;
 use crate::dns_operator::watcher_file::file_watcher;
 
+#[allow(dead_code)]
 pub(crate) enum Event {
     Create,
     Update,
@@ -23,6 +25,15 @@ impl Watcher {
         }
     }
 
+    /// makes `watch` return an `Update` event at `deadline` if nothing changed by then.
+    pub(crate) fn set_deadline(&mut self, deadline: Option<Instant>) {
+        match self {
+            Watcher::Null => unimplemented!(),
+            Watcher::File(watcher) => watcher.deadline = deadline
+        }
+    }
+
+    #[allow(dead_code)]
     pub(crate) fn unwatch(&self) -> Result<(), String> {
         match self {
             Watcher::Null => unimplemented!(),
@@ -32,14 +43,17 @@ impl Watcher {
 }
 
 // FileWatcher
-pub(crate) struct FileWatcher {}
+pub(crate) struct FileWatcher {
+    deadline: Option<Instant>,
+}
 
 impl FileWatcher {
-    fn new() -> Self { Self{} }
+    fn new() -> Self { Self{ deadline: None } }
 
     fn watch(&self) -> Result<Event, String> {
-        file_watcher()?;
+        file_watcher(self.deadline)?;
         Ok(Event::Update)
     }
+    #[allow(dead_code)]
     fn unwatch(&self) -> Result<(), String> { unimplemented!()}
 }
\ No newline at end of file
@@ -1,24 +1,32 @@
+use std::fs;
 use std::path::Path;
 use notify::{Event, recommended_watcher, RecommendedWatcher, RecursiveMode, Watcher};
-use std::sync::mpsc::{channel, Receiver};
-use notify::EventKind::Modify;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant};
+use notify::EventKind::{Create, Modify, Remove};
+use crate::dns_manifest_parser::zone::{Zone, ZONES_DIR};
+use crate::dns_operator::corefile::COREFILE_PATH;
 
-const DNS_MANIFEST_PATH: &str = "./dns_manifest";
-const COREFILE_PATH: &str = "Corefile";
+// Writing a file emits several events (e.g. truncate then write): wait for the writer to settle.
+const DEBOUNCE: Duration = Duration::from_millis(200);
 
 
 
-// Create a watcher to watch `FILE_DNS_PATH`
-pub(crate) fn file_watcher() -> Result<(), String> {
+// Create a watcher to watch the dns manifests and the Corefile, returning at the latest at
+// `deadline`.
+pub(crate) fn file_watcher(deadline: Option<Instant>) -> Result<(), String> {
     let (tx, rx) = channel();
 
     // Create a watcher to watch the current directory
     let mut watcher = recommended_watcher(tx)
         .expect("failed to create watcher");
-    watch_path(&mut watcher, DNS_MANIFEST_PATH);
+    watch_path(&mut watcher, &Zone::default().manifest_path());
     watch_path(&mut watcher, COREFILE_PATH);
+    // zones are added and removed as files of the zones directory.
+    fs::create_dir_all(ZONES_DIR).map_err(|e| e.to_string())?;
+    watch_path(&mut watcher, ZONES_DIR);
 
-    await_modify_event(rx)
+    await_modify_event(rx, deadline)
 }
 
 fn watch_path(watcher: &mut RecommendedWatcher, path: &str) {
@@ -30,18 +38,29 @@ fn watch_path(watcher: &mut RecommendedWatcher, path: &str) {
 
 // await_event takes a rx as input, loop until it receives an event.
 // WARN: in the future please change this logic. This can lead to missing events in case
-fn await_modify_event(rx: Receiver<Result<Event, notify::Error>>) -> Result<(), String> {
+fn await_modify_event(
+    rx: Receiver<Result<Event, notify::Error>>,
+    deadline: Option<Instant>
+) -> Result<(), String> {
     // Loop indefinitely, waiting for notifications
     loop {
-        match rx.recv() {
+        let received = match deadline {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(RecvTimeoutError::from),
+        };
+        match received {
             Ok(event) => {
                 match event {
                     Ok(event) => {
-                        if let Modify(_) = event.kind { return Ok(()) }
+                        if let Create(_) | Modify(_) | Remove(_) = event.kind {
+                            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+                            return Ok(())
+                        }
                     },
                     Err(e) => println!("error: {:?}", e)
                 }
             }
+            Err(RecvTimeoutError::Timeout) => return Ok(()),
             Err(e) => println!("error: {:?}", e)
         }
     }
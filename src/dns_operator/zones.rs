@@ -0,0 +1,65 @@
+use std::path::Path;
+use crate::dns_manifest_parser::error::join_errors;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::zone::Zone;
+use crate::dns_operator::corefile::{sync_managed_section, write_if_changed, Corefile, ServerBlock, COREFILE_PATH};
+
+const SECTION: &str = "zones";
+
+// Declares the zones of the zones directory in the Corefile, serving the signed zone of the
+// `signed` zones. The default zone is declared by hand, as before; only its zone file is updated.
+// Zones whose manifest cannot be parsed or names another origin are left out, so that a broken
+// zone does not keep CoreDNS from serving the others.
+pub(crate) fn sync_zones(signed: &[Zone]) -> Result<(), String> {
+    if let Err(e) = sync_default_zone(signed.contains(&Zone::default())) {
+        println!("{}: zone file not updated: {}", COREFILE_PATH, e);
+    }
+    let mut blocks: Vec<ServerBlock> = Vec::new();
+    for zone in Zone::list()? {
+        let Some(origin) = zone.get_origin() else { continue };
+        let path = zone.manifest_path();
+        let checked = Manifest::from_path(&path, ParseMode::Lenient)
+            .map_err(|e| join_errors(&e))
+            .and_then(|(manifest, _)| zone.check_origin(&manifest));
+        if let Err(e) = checked {
+            println!("{}: not served: {}", path, e);
+            continue
+        }
+
+        let zone_file = match signed.contains(&zone) {
+            true => zone.signed_path(),
+            false => path,
+        };
+        blocks.push(ServerBlock::zone_file(&format!("{}.", origin), zone_file.trim_start_matches("./")));
+    }
+    sync_managed_section(SECTION, blocks)
+}
+
+// Points the `file` plugin of the blocks serving the default zone to its signed zone when it has
+// just been signed, and back to its manifest otherwise. Blocks serving another zone file are left as written.
+fn sync_default_zone(signed: bool) -> Result<(), String> {
+    let zone = Zone::default();
+    let path = zone.manifest_path();
+    if !Path::new(&path).exists() {
+        return Ok(())
+    }
+    let origin = match Manifest::from_path(&path, ParseMode::Lenient) {
+        Ok((manifest, _)) => manifest.get_origin().get_origin(),
+        Err(e) => {
+            println!("{}: zone file not updated: {}", path, join_errors(&e));
+            return Ok(())
+        }
+    };
+
+    let (manifest_file, signed_file) = (path.trim_start_matches("./"), zone.signed_path());
+    let signed_file = signed_file.trim_start_matches("./");
+    let (from, to) = match signed {
+        true => (manifest_file, signed_file),
+        false => (signed_file, manifest_file),
+    };
+    let mut corefile = Corefile::from_path(COREFILE_PATH)?;
+    for block in corefile.server_blocks_mut().filter(|b| b.serves(&origin)) {
+        block.replace_zone_file(from, to);
+    }
+    write_if_changed(COREFILE_PATH, &corefile.to_string())
+}
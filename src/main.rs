@@ -15,7 +15,7 @@ use crate::dns_operator::watcher::Watcher;
 fn rocket() -> _ {
     thread::spawn(|| {
         OperatorBuilder::new()
-            .set_controller(Controller::new_default())
+            .set_controller(Controller::new_default().with_zones().with_reverse_zones().with_signing())
             .set_runtime(Runtime::new_coredns())
             .set_watcher(Watcher::new_file_watcher())
             .build()
@@ -1,3 +1,17 @@
 pub(crate) mod mount;
 mod a_record;
-mod manifest;
\ No newline at end of file
+mod aaaa_record;
+mod cname_record;
+mod config;
+mod corefile;
+mod diff;
+mod error;
+mod export;
+mod history;
+mod manifest;
+mod mx_record;
+mod srv_record;
+mod txt_record;
+mod unknown_record;
+mod validate;
+mod zones;
\ No newline at end of file
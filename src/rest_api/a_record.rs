@@ -1,70 +1,104 @@
-use std::borrow::Borrow;
-use std::fs;
 use rocket::serde::{json::Json};
-use crate::dns_manifest_parser::record::{Record, RecordData};
+use rocket::State;
+use crate::dns_manifest_parser::record::{ARdata, Record, RecordData};
 use crate::dns_manifest_parser::record;
-use crate::dns_manifest_parser::manifest::ManifestBuilder;
-
-const MANIFEST_PATH: &str = "./dns_manifest";
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::lookup::Answer;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::error::{expect_rrset, load, ApiError};
+use crate::rest_api::history::write_manifest;
 
 #[get("/")]
-pub(crate) fn get_a() -> &'static str {
-    "unimplemented"
+pub(crate) fn get_a(zone: &Zone) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(record::A)))
 }
 
 #[get("/<name>")]
-pub(crate) fn get_a_by_name(name: &str) -> Result<String, String> {
-    let builder = &mut ManifestBuilder::from_path(MANIFEST_PATH).unwrap();
-    if let Some((_, record)) = builder
-        .get_record_by(name, record::A) {
-        return Ok(record.to_string())
-    }
-    Err("no match found".to_string())
+pub(crate) fn get_a_by_name(name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, record::A))
 }
 
-// curl -XPOST 127.0.0.1:8000/a --data '{"name": "yolo.com.", "class": "IN", "record_type": "A", "value": "127.0.0.1"}'
+// curl -XPOST 127.0.0.1:8000/a --data '{"name": "yolo", "ttl": 60, "class": "IN", "record_type": "A", "value": "127.0.0.1"}'
 #[post("/", data = "<record>")]
-pub(crate) fn create_a(record: Json<Record>) -> String {
-    let builder= &mut ManifestBuilder::from_path(MANIFEST_PATH).unwrap();
+pub(crate) fn create_a(record: Json<Record<ARdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
     let record_data = RecordData::A(Record::from_json(record));
+    record_data.validate()?;
     let manifest = builder
-        .add_record(record_data)
-        .increment()
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
         .build()
         .to_string();
-    write_manifest(&manifest).expect("an error occurred while writing manifest");
-    manifest
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
 }
 
-// curl -XPUT 127.0.0.1:8000/a/yolo.com. --data '{"name": "yolo.com.", "class": "IN", "record_type": "A", "value": "10.0.0.1"}'
+// curl -XPUT 127.0.0.1:8000/a/yolo --data '{"name": "yolo", "class": "IN", "record_type": "A", "value": "10.0.0.1"}'
 #[put("/<name>", data = "<record>")]
-pub(crate) fn update_a(name: &str, record: Json<Record>) -> String {
-    let builder= &mut ManifestBuilder::from_path(MANIFEST_PATH).unwrap();
+pub(crate) fn update_a(name: &str, record: Json<Record<ARdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
     let record_data = RecordData::A(Record::from_json(record));
+    record_data.validate()?;
+    expect_rrset(builder, name, record_data.get_type())?;
     let manifest = builder
-        .update_record(name, record_data)
-        .increment()
+        .update_record(name, record_data)?
+        .check()?
+        .increment(config.serial_policy)
         .build()
         .to_string();
-    write_manifest(&manifest).expect("an error occurred while writing manifest");
-    manifest
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
 }
 
-// curl -XDELETE 127.0.0.1:8000/a/yolo.com.
+// curl -XDELETE 127.0.0.1:8000/a/yolo
 #[delete("/<name>")]
-pub(crate) fn delete_a(name: &str) -> Result<String, String> {
-    let builder= &mut ManifestBuilder::from_path(MANIFEST_PATH).unwrap();
+pub(crate) fn delete_a(name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
     let manifest = builder
         .delete_record(name, record::A)
-        .increment()
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/a/yolo/10.0.0.1
+#[delete("/<name>/<value>")]
+pub(crate) fn delete_a_value(name: &str, value: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let value = RecordData::from_rdata(name, record::A, value)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record_value(&value).map_err(ApiError::NotFound)?
+        .increment(config.serial_policy)
         .build()
         .to_string();
-    write_manifest(&manifest).expect("an error occurred while writing manifest");
+    write_manifest(zone, &manifest)?;
     Ok(manifest)
 }
 
 // --------------------------------------------- utils ---------------------------------------------
 
-pub(crate) fn write_manifest(content: &str) -> std::io::Result<()> {
-    fs::write(MANIFEST_PATH, content)
-}
\ No newline at end of file
+/// formats the records answering a lookup, e.g. those of a wildcard.
+pub(crate) fn format_answer(answer: Answer) -> Result<String, ApiError> {
+    match answer {
+        Answer::Exact(records) | Answer::Wildcard(records) => Ok(format_records(records)),
+        Answer::NoData | Answer::NxDomain => Err(ApiError::NotFound("no match found".to_string())),
+    }
+}
+
+pub(crate) fn format_records(records: Vec<&RecordData>) -> String {
+    records
+        .iter()
+        .map(|r| r.to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
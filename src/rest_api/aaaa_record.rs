@@ -0,0 +1,86 @@
+use rocket::serde::{json::Json};
+use rocket::State;
+use crate::dns_manifest_parser::record::{AAAARdata, Record, RecordData};
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::a_record::{format_answer, format_records};
+use crate::rest_api::error::{expect_rrset, load, ApiError};
+use crate::rest_api::history::write_manifest;
+
+#[get("/")]
+pub(crate) fn get_aaaa(zone: &Zone) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(record::AAAA)))
+}
+
+#[get("/<name>")]
+pub(crate) fn get_aaaa_by_name(name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, record::AAAA))
+}
+
+// curl -XPOST 127.0.0.1:8000/aaaa --data '{"name": "yolo", "class": "IN", "record_type": "AAAA", "value": "fd00::1"}'
+#[post("/", data = "<record>")]
+pub(crate) fn create_aaaa(record: Json<Record<AAAARdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::AAAA(Record::from_json(record));
+    record_data.validate()?;
+    let manifest = builder
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XPUT 127.0.0.1:8000/aaaa/yolo --data '{"name": "yolo", "class": "IN", "record_type": "AAAA", "value": "fd00::2"}'
+#[put("/<name>", data = "<record>")]
+pub(crate) fn update_aaaa(name: &str, record: Json<Record<AAAARdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::AAAA(Record::from_json(record));
+    record_data.validate()?;
+    expect_rrset(builder, name, record_data.get_type())?;
+    let manifest = builder
+        .update_record(name, record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/aaaa/yolo
+#[delete("/<name>")]
+pub(crate) fn delete_aaaa(name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record(name, record::AAAA)
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/aaaa/yolo/fd00::2
+#[delete("/<name>/<value>")]
+pub(crate) fn delete_aaaa_value(name: &str, value: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let value = RecordData::from_rdata(name, record::AAAA, value)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record_value(&value).map_err(ApiError::NotFound)?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
@@ -0,0 +1,71 @@
+use rocket::serde::{json::Json};
+use rocket::State;
+use crate::dns_manifest_parser::record::{CNAMERdata, Record, RecordData};
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::a_record::{format_answer, format_records};
+use crate::rest_api::error::{expect_rrset, load, ApiError};
+use crate::rest_api::history::write_manifest;
+
+#[get("/")]
+pub(crate) fn get_cname(zone: &Zone) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(record::CNAME)))
+}
+
+#[get("/<name>")]
+pub(crate) fn get_cname_by_name(name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, record::CNAME))
+}
+
+// curl -XPOST 127.0.0.1:8000/cname --data '{"name": "registry", "class": "IN", "record_type": "CNAME", "value": "vm-42"}'
+#[post("/", data = "<record>")]
+pub(crate) fn create_cname(record: Json<Record<CNAMERdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::CNAME(Record::from_json(record));
+    record_data.validate()?;
+    let manifest = builder
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XPUT 127.0.0.1:8000/cname/registry --data '{"name": "registry", "class": "IN", "record_type": "CNAME", "value": "vm-43"}'
+#[put("/<name>", data = "<record>")]
+pub(crate) fn update_cname(name: &str, record: Json<Record<CNAMERdata>>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::CNAME(Record::from_json(record));
+    record_data.validate()?;
+    expect_rrset(builder, name, record_data.get_type())?;
+    let manifest = builder
+        .update_record(name, record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/cname/registry
+#[delete("/<name>")]
+pub(crate) fn delete_cname(name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record(name, record::CNAME)
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
@@ -0,0 +1,12 @@
+use rocket::serde::Deserialize;
+use crate::dns_manifest_parser::serial::SerialPolicy;
+
+// Config holds the settings of betterdns read by Rocket, i.e. from `Rocket.toml` or from
+// `ROCKET_`-prefixed environment variables, e.g. `ROCKET_SERIAL_POLICY=date`.
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Config {
+    // how the serial of the SOA record moves forward: `counter`, `date` or `unixtime`.
+    #[serde(default)]
+    pub(crate) serial_policy: SerialPolicy,
+}
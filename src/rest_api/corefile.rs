@@ -0,0 +1,71 @@
+use crate::dns_operator::corefile::{write_if_changed, Corefile, Directive, ServerBlock, COREFILE_PATH};
+use crate::rest_api::error::ApiError;
+
+// Plugins betterdns toggles on server blocks; others are configured by hand.
+const PLUGINS: [&str; 4] = ["log", "cache", "forward", "prometheus"];
+
+// curl 127.0.0.1:8000/corefile
+#[get("/")]
+pub(crate) fn get_corefile() -> Result<String, ApiError> {
+    Ok(Corefile::from_path(COREFILE_PATH).map_err(ApiError::Internal)?.to_string())
+}
+
+// curl -XPUT 127.0.0.1:8000/corefile/log
+// curl -XPUT '127.0.0.1:8000/corefile/forward?zone=example.org' --data '. 1.1.1.1 8.8.8.8'
+// Enables a plugin on every server block, or on those serving `zone`; the body holds its arguments.
+#[put("/<plugin>?<zone>", data = "<args>")]
+pub(crate) fn enable_plugin(plugin: &str, zone: Option<&str>, args: String) -> Result<String, ApiError> {
+    let plugin = parse_plugin(plugin)?;
+    let args: Vec<String> = match args.split_whitespace().map(str::to_string).collect::<Vec<String>>() {
+        args if args.is_empty() => default_args(plugin),
+        args => args,
+    };
+    toggle(zone, |block| {
+        block.set_plugin(Directive::new(plugin, args.clone()));
+    })
+}
+
+// curl -XDELETE '127.0.0.1:8000/corefile/cache?zone=example.org'
+#[delete("/<plugin>?<zone>")]
+pub(crate) fn disable_plugin(plugin: &str, zone: Option<&str>) -> Result<String, ApiError> {
+    let plugin = parse_plugin(plugin)?;
+    toggle(zone, |block| {
+        block.remove_plugin(plugin);
+    })
+}
+
+// toggle applies `f` to the server blocks serving `zone`, or to all of them, and writes the
+// Corefile; the operator restarts the nameserver when it changes.
+fn toggle(zone: Option<&str>, f: impl FnMut(&mut ServerBlock)) -> Result<String, ApiError> {
+    let mut corefile = Corefile::from_path(COREFILE_PATH).map_err(ApiError::Internal)?;
+    let blocks: Vec<&mut ServerBlock> = corefile
+        .server_blocks_mut()
+        .filter(|b| zone.is_none_or(|z| b.serves(z)))
+        .collect();
+    if blocks.is_empty() {
+        return Err(ApiError::NotFound(match zone {
+            Some(zone) => format!("no server block serves {}", zone),
+            None => "the Corefile has no server block".to_string(),
+        }))
+    }
+    blocks.into_iter().for_each(f);
+
+    let content = corefile.to_string();
+    write_if_changed(COREFILE_PATH, &content).map_err(ApiError::Internal)?;
+    Ok(content)
+}
+
+fn parse_plugin(plugin: &str) -> Result<&'static str, String> {
+    PLUGINS
+        .into_iter()
+        .find(|p| *p == plugin)
+        .ok_or_else(|| format!("plugin should be one of {}, received: {}", PLUGINS.join(", "), plugin))
+}
+
+// default_args forwards queries to the resolvers of the host; other plugins need no argument.
+fn default_args(plugin: &str) -> Vec<String> {
+    match plugin {
+        "forward" => vec![".".to_string(), "/etc/resolv.conf".to_string()],
+        _ => vec![],
+    }
+}
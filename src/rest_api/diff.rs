@@ -0,0 +1,35 @@
+use std::path::Path;
+use rocket::serde::json;
+use crate::dns_manifest_parser::error::join_errors;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::error::ApiError;
+use crate::rest_api::history::history_path;
+
+// curl '127.0.0.1:8000/diff?from=2022101100&to=2022101101'
+// curl '127.0.0.1:8000/diff?from=2022101100&format=json'
+// `to` defaults to the current manifest.
+#[get("/?<from>&<to>&<format>")]
+pub(crate) fn get_diff(from: u32, to: Option<u32>, format: Option<&str>, zone: &Zone) -> Result<String, ApiError> {
+    let from = load(&history_path(zone, from))?;
+    let to = match to {
+        Some(serial) => load(&history_path(zone, serial))?,
+        None => load(&zone.manifest_path())?,
+    };
+    let diff = from.diff(&to);
+    match format {
+        Some("json") => json::to_string(&diff).map_err(|e| ApiError::Internal(e.to_string())),
+        _ => Ok(diff.to_string()),
+    }
+}
+
+// load fails with not found for serials missing from the history.
+fn load(path: &str) -> Result<Manifest, ApiError> {
+    if !Path::new(path).exists() {
+        return Err(ApiError::NotFound(format!("no manifest found at {}", path)))
+    }
+    match Manifest::from_path(path, ParseMode::Lenient) {
+        Ok((manifest, _)) => Ok(manifest),
+        Err(e) => Err(ApiError::Internal(join_errors(&e))),
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::Path;
+use crate::dns_manifest_parser::error::ParseError;
+use crate::dns_manifest_parser::manifest::ManifestBuilder;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+
+/// ApiError is the response of a failed request, its status telling the client whether to fix the
+/// request or not. A `String` error is a bad request, e.g. a record failing validation; an IO error
+/// is the server's.
+#[derive(Debug, Responder)]
+pub(crate) enum ApiError {
+    #[response(status = 400)]
+    BadRequest(String),
+    #[response(status = 404)]
+    NotFound(String),
+    #[response(status = 500)]
+    Internal(String),
+}
+
+impl From<String> for ApiError {
+    fn from(e: String) -> Self {
+        Self::BadRequest(e)
+    }
+}
+
+impl From<ParseError> for ApiError {
+    fn from(e: ParseError) -> Self {
+        Self::BadRequest(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Internal(e.to_string())
+    }
+}
+
+/// loads the manifest of the zone. A zone without manifest is not found; a manifest which cannot
+/// be parsed, e.g. broken by hand, is an error of the server.
+pub(crate) fn load(zone: &Zone) -> Result<ManifestBuilder, ApiError> {
+    let path = zone.manifest_path();
+    if !Path::new(&path).exists() {
+        return Err(ApiError::NotFound(format!("no manifest found at {}", path)))
+    }
+    ManifestBuilder::from_path(&path).map_err(ApiError::Internal)
+}
+
+/// fails when the zone has no RRset of `name` and `_type`, e.g. before replacing it.
+pub(crate) fn expect_rrset(builder: &ManifestBuilder, name: &DomainName, _type: &str) -> Result<(), ApiError> {
+    match builder.get_rrset(name, _type).is_empty() {
+        true => Err(ApiError::NotFound(format!("no {} record found for `{}`", _type, name))),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::http::Status;
+    use rocket::local::blocking::Client;
+    use super::{expect_rrset, load, ApiError};
+    use crate::dns_manifest_parser::manifest::{Manifest, ManifestBuilder, ParseMode};
+    use crate::dns_manifest_parser::name::DomainName;
+    use crate::dns_manifest_parser::record;
+    use crate::dns_manifest_parser::zone::Zone;
+
+    #[get("/<kind>")]
+    fn fail(kind: &str) -> Result<String, ApiError> {
+        match kind {
+            "io" => Err(std::io::Error::other("disk full"))?,
+            "missing" => Err(ApiError::NotFound("no match found".to_string())),
+            _ => Err(format!("invalid {}", kind))?,
+        }
+    }
+
+    #[test]
+    fn errors_are_answered_with_their_status() {
+        let client = Client::untracked(rocket::build().mount("/", routes![fail])).unwrap();
+        for (kind, status, body) in [
+            ("io", Status::InternalServerError, "disk full"),
+            ("missing", Status::NotFound, "no match found"),
+            ("name", Status::BadRequest, "invalid name"),
+        ] {
+            let response = client.get(format!("/{}", kind)).dispatch();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.into_string().as_deref(), Some(body));
+        }
+    }
+
+    #[test]
+    fn missing_zones_and_rrsets_are_not_found() {
+        let zone = Zone::new("missing.invalid").unwrap();
+        assert!(matches!(load(&zone), Err(ApiError::NotFound(_))));
+
+        let zone = "$ORIGIN example.com.\n$TTL 3600\n@ IN SOA ns1 admin 1 2 3 4 5\nwww IN A 10.0.0.1\n";
+        let (manifest, _) = Manifest::parse(zone, ParseMode::Strict).unwrap();
+        let builder = ManifestBuilder::from(manifest);
+        assert!(expect_rrset(&builder, &DomainName::parse("www").unwrap(), record::A).is_ok());
+        assert!(expect_rrset(&builder, &DomainName::parse("WWW.example.com.").unwrap(), record::A).is_ok());
+        assert!(matches!(
+            expect_rrset(&builder, &DomainName::parse("www").unwrap(), record::AAAA),
+            Err(ApiError::NotFound(e)) if e == "no AAAA record found for `www`"
+        ));
+    }
+}
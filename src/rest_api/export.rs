@@ -0,0 +1,13 @@
+use crate::dns_manifest_parser::export::ExportFormat;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::error::{load, ApiError};
+
+// curl 127.0.0.1:8000/export/hosts
+// curl 127.0.0.1:8000/export/dnsmasq
+// curl 127.0.0.1:8000/export/unbound
+#[get("/<format>")]
+pub(crate) fn get_export(format: &str, zone: &Zone) -> Result<String, ApiError> {
+    let format = ExportFormat::parse(format)?;
+    let manifest = load(zone)?.build();
+    Ok(format.render(&manifest))
+}
@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::zone::Zone;
+
+/// writes the manifest of the zone, keeping a snapshot of each version in its history directory,
+/// e.g. `./history/<serial>`. The version being replaced is kept too, as it may have been edited by
+/// hand. The manifest is replaced atomically, so that the operator never reads a partial manifest.
+pub(crate) fn write_manifest(zone: &Zone, content: &str) -> std::io::Result<()> {
+    let path = zone.manifest_path();
+    if let Ok(previous) = fs::read_to_string(&path) {
+        snapshot(zone, &previous, false)?
+    }
+    // writing to a zone creates it.
+    if let Some(dir) = Path::new(&path).parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = zone.tmp_path();
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, &path)?;
+    snapshot(zone, content, true)
+}
+
+/// returns the path of the snapshot of the zone at `serial`.
+pub(crate) fn history_path(zone: &Zone, serial: u32) -> String {
+    format!("{}/{}", zone.history_dir(), serial)
+}
+
+// snapshot skips manifests that cannot be parsed, as they have no serial.
+fn snapshot(zone: &Zone, content: &str, overwrite: bool) -> std::io::Result<()> {
+    let serial = match Manifest::parse(content, ParseMode::Lenient) {
+        Ok((manifest, _)) => manifest.get_soa().get_serial(),
+        Err(_) => return Ok(()),
+    };
+    let path = history_path(zone, serial);
+    if !overwrite && Path::new(&path).exists() {
+        return Ok(())
+    }
+    fs::create_dir_all(zone.history_dir())?;
+    fs::write(path, content)
+}
@@ -1,11 +1,96 @@
-use crate::dns_manifest_parser::manifest::ManifestBuilder;
-
-const MANIFEST_PATH: &str = "./dns_manifest";
+use std::fs;
+use std::io::ErrorKind;
+use rocket::http::{Accept, ContentType, MediaType};
+use rocket::serde::json::Json;
+use rocket::State;
+use crate::dns_manifest_parser::error::ParseError;
+use crate::dns_manifest_parser::format::Format;
+use crate::dns_manifest_parser::manifest::{Manifest, ManifestBuilder, ParseMode};
+use crate::dns_manifest_parser::validator;
+use crate::dns_manifest_parser::validator::Severity;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::history::write_manifest;
+use crate::rest_api::config::Config;
+use crate::rest_api::error::{load, ApiError};
 
+// curl 127.0.0.1:8000 -H 'accept: application/yaml'
+// curl 127.0.0.1:8000 -H 'accept: application/octet-stream' -o zone.bin
 #[get("/")]
-pub(crate) fn get() -> String {
-    ManifestBuilder::from_path(MANIFEST_PATH)
-        .unwrap()
+pub(crate) fn get(accept: Option<&Accept>, zone: &Zone) -> Result<(ContentType, Vec<u8>), ApiError> {
+    let format = accept.map_or(Format::Zone, |a| format_of(a.preferred().media_type()));
+    let manifest = load(zone)?.build();
+    Ok((content_type_of(format), format.encode(&manifest).map_err(ApiError::Internal)?))
+}
+
+// curl -XPUT 127.0.0.1:8000 -H 'content-type: application/json' --data-binary @manifest.json
+// curl -XPUT 127.0.0.1:8000/zones/example.org --data-binary @example.org.zone
+// Replaces the whole manifest, or creates the zone; its serial moves forward from the current one.
+#[put("/", data = "<body>")]
+pub(crate) fn import(body: Vec<u8>, content_type: Option<&ContentType>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let format = content_type.map_or(Format::Zone, |c| format_of(c.media_type()));
+    let manifest = format.decode(&body)?;
+    zone.check_origin(&manifest)?;
+
+    let errors: Vec<String> = validator::validate(&manifest)
+        .iter()
+        .filter(|d| d.get_severity() == Severity::Error)
+        .map(|d| d.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(ApiError::BadRequest(errors.join("\n")))
+    }
+
+    let builder = &mut ManifestBuilder::from(manifest);
+    if let Ok((current, _)) = Manifest::from_path(&zone.manifest_path(), ParseMode::Lenient) {
+        builder.catch_up(current.get_soa().get_serial());
+    }
+    let manifest = builder
+        .increment(config.serial_policy)
         .build()
-        .to_string()
-}
\ No newline at end of file
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/zones/example.org
+// Stops serving a zone of the zones directory; its history is kept.
+#[delete("/")]
+pub(crate) fn delete_zone(zone: &Zone) -> Result<String, ApiError> {
+    if zone.get_origin().is_none() {
+        return Err(ApiError::BadRequest("the default zone cannot be deleted".to_string()))
+    }
+    let path = zone.manifest_path();
+    fs::remove_file(&path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => ApiError::NotFound(format!("{}: {}", path, e)),
+        _ => ApiError::Internal(format!("{}: {}", path, e)),
+    })?;
+    Ok(path)
+}
+
+// curl 127.0.0.1:8000/diagnostics
+#[get("/diagnostics")]
+pub(crate) fn get_diagnostics(zone: &Zone) -> Json<Vec<ParseError>> {
+    match Manifest::from_path(&zone.manifest_path(), ParseMode::Lenient) {
+        Ok((_, diagnostics)) => Json(diagnostics),
+        Err(errors) => Json(errors),
+    }
+}
+
+// format_of defaults to zonefiles, e.g. for `text/plain` or `*/*`.
+fn format_of(media_type: &MediaType) -> Format {
+    match media_type.sub().as_str() {
+        "json" => Format::Json,
+        "yaml" | "x-yaml" => Format::Yaml,
+        "octet-stream" => Format::Wire,
+        _ => Format::Zone,
+    }
+}
+
+fn content_type_of(format: Format) -> ContentType {
+    match format {
+        Format::Zone => ContentType::Plain,
+        Format::Json => ContentType::JSON,
+        Format::Yaml => ContentType::new("application", "yaml"),
+        Format::Wire => ContentType::Binary,
+    }
+}
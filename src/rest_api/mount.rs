@@ -1,14 +1,62 @@
 use rocket::{Build, Rocket};
-use crate::rest_api::manifest::get;
-use crate::rest_api::a_record::{get_a, get_a_by_name, create_a, update_a, delete_a};
+use rocket::fairing::AdHoc;
+use crate::rest_api::config::Config;
+use crate::rest_api::corefile::{get_corefile, enable_plugin, disable_plugin};
+use crate::rest_api::diff::get_diff;
+use crate::rest_api::export::get_export;
+use crate::rest_api::manifest::{get, import, delete_zone, get_diagnostics};
+use crate::rest_api::a_record::{get_a, get_a_by_name, create_a, update_a, delete_a, delete_a_value};
+use crate::rest_api::aaaa_record::{get_aaaa, get_aaaa_by_name, create_aaaa, update_aaaa, delete_aaaa, delete_aaaa_value};
+use crate::rest_api::cname_record::{get_cname, get_cname_by_name, create_cname, update_cname, delete_cname};
+use crate::rest_api::mx_record::{get_mx, get_mx_by_name, create_mx, update_mx, delete_mx, delete_mx_value};
+use crate::rest_api::srv_record::{get_srv, get_srv_by_name, create_srv, update_srv, delete_srv, delete_srv_value};
+use crate::rest_api::validate::validate_zone;
+use crate::rest_api::txt_record::{get_txt, get_txt_by_name, create_txt, update_txt, delete_txt, delete_txt_value};
+use crate::rest_api::unknown_record::{get_unknown, get_unknown_by_name, create_unknown, delete_unknown};
+use crate::rest_api::zones::{get_zones, zone_router};
 
 const A_RECORD_ROUTE: &str = "/a";
+const AAAA_RECORD_ROUTE: &str = "/aaaa";
+const CNAME_RECORD_ROUTE: &str = "/cname";
+const COREFILE_ROUTE: &str = "/corefile";
+const DIFF_ROUTE: &str = "/diff";
+const EXPORT_ROUTE: &str = "/export";
+const MX_RECORD_ROUTE: &str = "/mx";
+const SRV_RECORD_ROUTE: &str = "/srv";
+const TXT_RECORD_ROUTE: &str = "/txt";
+const UNKNOWN_RECORD_ROUTE: &str = "/unknown";
+const VALIDATE_ROUTE: &str = "/validate";
+const ZONES_ROUTE: &str = "/zones";
 const ROOT: &str = "/";
 
 pub(crate) fn mount() -> Rocket<Build> {
     rocket::build()
+        .attach(AdHoc::config::<Config>())
+        .attach(zone_router())
         // Manifest
-        .mount(ROOT, routes![get])
+        .mount(ROOT, routes![get, import, delete_zone, get_diagnostics])
+        // Zones
+        .mount(ZONES_ROUTE, routes![get_zones])
         // A Records
-        .mount(A_RECORD_ROUTE, routes![get_a,get_a_by_name, create_a, update_a,delete_a])
+        .mount(A_RECORD_ROUTE, routes![get_a,get_a_by_name, create_a, update_a,delete_a, delete_a_value])
+        // AAAA Records
+        .mount(AAAA_RECORD_ROUTE, routes![get_aaaa, get_aaaa_by_name, create_aaaa, update_aaaa, delete_aaaa, delete_aaaa_value])
+        // CNAME Records
+        .mount(CNAME_RECORD_ROUTE, routes![get_cname, get_cname_by_name, create_cname, update_cname, delete_cname])
+        // MX Records
+        .mount(MX_RECORD_ROUTE, routes![get_mx, get_mx_by_name, create_mx, update_mx, delete_mx, delete_mx_value])
+        // SRV Records
+        .mount(SRV_RECORD_ROUTE, routes![get_srv, get_srv_by_name, create_srv, update_srv, delete_srv, delete_srv_value])
+        // TXT Records
+        .mount(TXT_RECORD_ROUTE, routes![get_txt, get_txt_by_name, create_txt, update_txt, delete_txt, delete_txt_value])
+        // Records of other types, in the generic form of RFC 3597
+        .mount(UNKNOWN_RECORD_ROUTE, routes![get_unknown, get_unknown_by_name, create_unknown, delete_unknown])
+        // Validation
+        .mount(VALIDATE_ROUTE, routes![validate_zone])
+        // Corefile
+        .mount(COREFILE_ROUTE, routes![get_corefile, enable_plugin, disable_plugin])
+        // Diff
+        .mount(DIFF_ROUTE, routes![get_diff])
+        // Export
+        .mount(EXPORT_ROUTE, routes![get_export])
 }
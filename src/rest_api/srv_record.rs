@@ -0,0 +1,86 @@
+use rocket::serde::{json::Json};
+use rocket::State;
+use crate::dns_manifest_parser::record::{SRVRecord, RecordData};
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::a_record::{format_answer, format_records};
+use crate::rest_api::error::{expect_rrset, load, ApiError};
+use crate::rest_api::history::write_manifest;
+
+#[get("/")]
+pub(crate) fn get_srv(zone: &Zone) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(record::SRV)))
+}
+
+#[get("/<name>")]
+pub(crate) fn get_srv_by_name(name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, record::SRV))
+}
+
+// curl -XPOST 127.0.0.1:8000/srv --data '{"name": "_http._tcp.web", "class": "IN", "priority": 10, "weight": 5, "port": 8080, "target": "vm-3"}'
+#[post("/", data = "<record>")]
+pub(crate) fn create_srv(record: Json<SRVRecord>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::SRV(SRVRecord::from_json(record));
+    record_data.validate()?;
+    let manifest = builder
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XPUT 127.0.0.1:8000/srv/_http._tcp.web --data '{"name": "_http._tcp.web", "class": "IN", "priority": 10, "weight": 5, "port": 8081, "target": "vm-3"}'
+#[put("/<name>", data = "<record>")]
+pub(crate) fn update_srv(name: &str, record: Json<SRVRecord>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::SRV(SRVRecord::from_json(record));
+    record_data.validate()?;
+    expect_rrset(builder, name, record_data.get_type())?;
+    let manifest = builder
+        .update_record(name, record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/srv/_http._tcp.web
+#[delete("/<name>")]
+pub(crate) fn delete_srv(name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record(name, record::SRV)
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/srv/_http._tcp.web/10%205%208080%20vm-3
+#[delete("/<name>/<value>")]
+pub(crate) fn delete_srv_value(name: &str, value: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let value = RecordData::from_rdata(name, record::SRV, value)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record_value(&value).map_err(ApiError::NotFound)?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
@@ -0,0 +1,86 @@
+use rocket::serde::{json::Json};
+use rocket::State;
+use crate::dns_manifest_parser::record::{TXTRecord, RecordData};
+use crate::dns_manifest_parser::record;
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::a_record::{format_answer, format_records};
+use crate::rest_api::error::{expect_rrset, load, ApiError};
+use crate::rest_api::history::write_manifest;
+
+#[get("/")]
+pub(crate) fn get_txt(zone: &Zone) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(record::TXT)))
+}
+
+#[get("/<name>")]
+pub(crate) fn get_txt_by_name(name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, record::TXT))
+}
+
+// curl -XPOST 127.0.0.1:8000/txt --data '{"name": "@", "class": "IN", "strings": ["v=spf1 ip4:10.0.0.0/8 ~all"]}'
+#[post("/", data = "<record>")]
+pub(crate) fn create_txt(record: Json<TXTRecord>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::TXT(TXTRecord::from_json(record));
+    record_data.validate()?;
+    let manifest = builder
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XPUT 127.0.0.1:8000/txt/@ --data '{"name": "@", "class": "IN", "strings": ["v=spf1 -all"]}'
+#[put("/<name>", data = "<record>")]
+pub(crate) fn update_txt(name: &str, record: Json<TXTRecord>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::TXT(TXTRecord::from_json(record));
+    record_data.validate()?;
+    expect_rrset(builder, name, record_data.get_type())?;
+    let manifest = builder
+        .update_record(name, record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/txt/@
+#[delete("/<name>")]
+pub(crate) fn delete_txt(name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record(name, record::TXT)
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/txt/@/%22v%3Dspf1%20-all%22
+#[delete("/<name>/<value>")]
+pub(crate) fn delete_txt_value(name: &str, value: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let name = &DomainName::parse(name)?;
+    let value = RecordData::from_rdata(name, record::TXT, value)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record_value(&value).map_err(ApiError::NotFound)?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
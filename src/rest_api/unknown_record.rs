@@ -0,0 +1,63 @@
+use rocket::serde::{json::Json};
+use rocket::State;
+use crate::dns_manifest_parser::record::{type_code, type_name, UnknownRecord, RecordData};
+use crate::dns_manifest_parser::name::DomainName;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::config::Config;
+use crate::rest_api::a_record::{format_answer, format_records};
+use crate::rest_api::error::{load, ApiError};
+use crate::rest_api::history::write_manifest;
+
+// curl 127.0.0.1:8000/unknown/TYPE65534
+#[get("/<record_type>")]
+pub(crate) fn get_unknown(record_type: &str, zone: &Zone) -> Result<String, ApiError> {
+    let record_type = parse_type(record_type)?;
+    let builder = &mut load(zone)?;
+    Ok(format_records(builder.list_records(&record_type)))
+}
+
+#[get("/<record_type>/<name>")]
+pub(crate) fn get_unknown_by_name(record_type: &str, name: &str, zone: &Zone) -> Result<String, ApiError> {
+    let record_type = parse_type(record_type)?;
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    format_answer(builder.lookup(name, &record_type))
+}
+
+// curl -XPOST 127.0.0.1:8000/unknown --data '{"name": "@", "class": "IN", "record_type": "TYPE65534", "rdata": "0A000001"}'
+#[post("/", data = "<record>")]
+pub(crate) fn create_unknown(record: Json<UnknownRecord>, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let builder = &mut load(zone)?;
+    let record_data = RecordData::Unknown(UnknownRecord::from_json(record));
+    record_data.validate()?;
+    let manifest = builder
+        .add_record(record_data)?
+        .check()?
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// curl -XDELETE 127.0.0.1:8000/unknown/TYPE65534/@
+#[delete("/<record_type>/<name>")]
+pub(crate) fn delete_unknown(record_type: &str, name: &str, zone: &Zone, config: &State<Config>) -> Result<String, ApiError> {
+    let record_type = parse_type(record_type)?;
+    let name = &DomainName::parse(name)?;
+    let builder = &mut load(zone)?;
+    let manifest = builder
+        .delete_record(name, &record_type)
+        .increment(config.serial_policy)
+        .build()
+        .to_string();
+    write_manifest(zone, &manifest)?;
+    Ok(manifest)
+}
+
+// parse_type reads a type as records hold it: its mnemonic if known, `TYPEnnn` otherwise.
+fn parse_type(record_type: &str) -> Result<String, String> {
+    type_code(record_type)
+        .map(type_name)
+        .ok_or_else(|| format!("record type should be a known mnemonic or written `TYPEnnn`, received: {}", record_type))
+}
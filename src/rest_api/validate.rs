@@ -0,0 +1,26 @@
+use rocket::serde::json::Json;
+use rocket::serde::Serialize;
+use crate::dns_manifest_parser::error::ParseError;
+use crate::dns_manifest_parser::manifest::{Manifest, ParseMode};
+use crate::dns_manifest_parser::validator;
+use crate::dns_manifest_parser::validator::{Diagnostic, Severity};
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+pub(crate) struct Report {
+    // the zone parsed without error and has no diagnostic of severity `Error`.
+    valid: bool,
+    parse_errors: Vec<ParseError>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+// curl -XPOST 127.0.0.1:8000/validate --data-binary @dns_manifest
+#[post("/", data = "<zone>")]
+pub(crate) fn validate_zone(zone: String) -> Json<Report> {
+    let (parse_errors, diagnostics) = match Manifest::parse(&zone, ParseMode::Lenient) {
+        Ok((manifest, errors)) => (errors, validator::validate(&manifest)),
+        Err(errors) => (errors, vec![]),
+    };
+    let valid = parse_errors.is_empty() && diagnostics.iter().all(|d| d.get_severity() != Severity::Error);
+    Json(Report{ valid, parse_errors, diagnostics })
+}
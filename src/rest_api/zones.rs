@@ -0,0 +1,45 @@
+use rocket::fairing::AdHoc;
+use rocket::http::uri::Origin;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use crate::dns_manifest_parser::zone::Zone;
+use crate::rest_api::error::ApiError;
+
+const ZONES_SEGMENT: &str = "zones";
+
+// curl 127.0.0.1:8000/zones
+// Lists the zones of the zones directory; the default zone is served at the root.
+#[get("/")]
+pub(crate) fn get_zones() -> Result<String, ApiError> {
+    Ok(Zone::list().map_err(ApiError::Internal)?
+        .iter()
+        .filter_map(|zone| zone.get_origin())
+        .map(|origin| format!("{}.\n", origin))
+        .collect())
+}
+
+/// routes `/zones/<origin>/<route>` to `/<route>` for the zone of `<origin>`, e.g.
+/// `/zones/example.org/a/www` to `/a/www`. Routes take the zone with a `&Zone` guard.
+pub(crate) fn zone_router() -> AdHoc {
+    AdHoc::on_request("Zone router", |req, _| Box::pin(async move {
+        let segments: Vec<&str> = req.uri().path().raw_segments().map(|s| s.as_str()).collect();
+        let (Some(&ZONES_SEGMENT), Some(origin)) = (segments.first(), segments.get(1)) else { return };
+        let Ok(zone) = Zone::new(origin) else { return };
+
+        let query = req.uri().query().map(|q| format!("?{}", q.as_str())).unwrap_or_default();
+        if let Ok(uri) = Origin::parse_owned(format!("/{}{}", segments[2..].join("/"), query)) {
+            req.set_uri(uri);
+            req.local_cache(|| zone);
+        }
+    }))
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for &'r Zone {
+    type Error = ();
+
+    // requests outside of `/zones/<origin>` are for the default zone.
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(req.local_cache(Zone::default))
+    }
+}